@@ -1,5 +1,11 @@
+use boolinator::Boolinator;
+use xcom_1_card::{
+    Forces, PanicTrack, TechDeck, ALIEN_PANIC_SPACE, ALL_CONTINENTS, MAX_SATELLITES,
+};
 use yew::prelude::*;
 
+use crate::tech_reference::tech_icon;
+
 pub fn inline_icon_text_phrase(icon: &str, title: &str) -> Html {
     // Ensure icon is attached to the first word in the phrase
     let mut words = title.split_whitespace();
@@ -43,3 +49,94 @@ pub enum Focus {
     TechReference,
     RulesReference,
 }
+
+// Draws the panic track with the marker on its current space
+pub fn panic_track_html(panic: &PanicTrack) -> Html {
+    html! {
+        <div class="panic-track">
+            {
+                (0..=ALIEN_PANIC_SPACE)
+                    .map(|space| {
+                        let level = String::from(PanicTrack::new(space).level());
+                        html! {
+                            <div class={classes!("panic-track-space", format!("panic-track-{}", level), (space == panic.space()).as_some("current"))}>
+                                {
+                                    if space == ALIEN_PANIC_SPACE {
+                                        html! { <img class="inline-icon" src="assets/icons/alien.png"/> }
+                                    } else {
+                                        html! { { space + 1 } }
+                                    }
+                                }
+                            </div>
+                        }
+                    })
+                    .collect::<Html>()
+            }
+        </div>
+    }
+}
+
+// Shows the interceptor reserves, deployed interceptors and satellites in the bottom panel.
+//   The satellite count becomes a button when satellites can be used
+pub fn forces_html(forces: &Forces, on_use_satellite: Option<Callback<MouseEvent>>) -> Html {
+    let satellites = html! {
+        <>
+            <img class="inline-icon" src="assets/icons/satellite.png"/>
+            {format!(" {}/{}", forces.satellites(), MAX_SATELLITES)}
+        </>
+    };
+    html! {
+        <div class="forces-panel">
+            <span class="forces-item" title="Interceptors in reserves">
+                <img class="inline-icon" src="assets/icons/interceptor.png"/>
+                {format!(" {}", forces.reserves())}
+            </span>
+            {
+                ALL_CONTINENTS
+                    .iter()
+                    .map(|continent| html! {
+                        <span class="forces-item" title={format!("Interceptors deployed to {}", continent)}>
+                            <img class="inline-icon" src={format!("assets/icons/{}.png", continent.lowercase())}/>
+                            {format!(" {}", forces.deployed_on(continent))}
+                        </span>
+                    })
+                    .collect::<Html>()
+            }
+            {
+                match on_use_satellite {
+                    Some(callback) => html! {
+                        <button class="forces-item forces-use-satellite" title="Use a satellite" onclick={callback} disabled={forces.satellites() < 1}>
+                            {satellites}
+                        </button>
+                    },
+                    None => html! {
+                        <span class="forces-item" title="Satellites">{satellites}</span>
+                    },
+                }
+            }
+        </div>
+    }
+}
+
+// Shows the tech being researched and the techs that have been researched
+pub fn research_html(tech_deck: &TechDeck) -> Html {
+    html! {
+        <div class="research-panel">
+            {
+                match tech_deck.researching() {
+                    Some(research) => inline_icon_text_phrase("research", &format!("Researching {}", research)),
+                    None => inline_icon_text_phrase("research", "No research selected"),
+                }
+            }
+            {
+                tech_deck
+                    .owned()
+                    .iter()
+                    .map(|tech| html! {
+                        <img class={classes!("research-panel-tech", tech_deck.is_exhausted(tech).as_some("exhausted"))} src={tech_icon(tech)} title={tech.name()}/>
+                    })
+                    .collect::<Html>()
+            }
+        </div>
+    }
+}
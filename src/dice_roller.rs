@@ -0,0 +1,298 @@
+use boolinator::Boolinator;
+use gloo_storage::{LocalStorage, Storage};
+use xcom_1_card::{
+    Task, TaskRoller, Tech, TechDeck, MAX_ALIEN_THREAT, MIN_ALIEN_THREAT, SUCCESS_DIE_THRESHOLD,
+};
+use yew::prelude::*;
+
+use crate::common::inline_icon_text_phrase;
+
+// Kept between prompts so the alien threat carries on until the task changes
+pub const TASK_ROLLER_KEY: &str = "DiceRoller_TaskRoller";
+
+// A task that can be rolled for, with the number of success dice the player would usually roll
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskOption {
+    pub task: Task,
+    pub success_dice: u32,
+}
+
+pub struct DiceRoller {
+    roller: TaskRoller,
+    open: bool,
+    selected_task_index: usize,
+    success_dice: u32,
+}
+
+pub enum Msg {
+    Toggle,
+    SelectTask(usize),
+    IncreaseSuccessDice,
+    DecreaseSuccessDice,
+    UseAlienIntel,
+    Roll,
+    UseCarapaceArmour,
+    UseEMPCannon,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    pub tasks: Vec<TaskOption>,
+    // With any techs used so far this round exhausted
+    pub tech_deck: TechDeck,
+    pub on_use_tech: Callback<Tech>,
+    pub storage_prefix: String,
+}
+
+impl Props {
+    fn storage_key(&self, key: &str) -> String {
+        format!("{}{}", self.storage_prefix, key)
+    }
+}
+
+impl DiceRoller {
+    fn selected_task<'a>(&self, props: &'a Props) -> Option<&'a TaskOption> {
+        props.tasks.get(self.selected_task_index)
+    }
+
+    // Only owned techs get a button, which is disabled once the tech has been used this round
+    fn tech_button_html(
+        &self,
+        ctx: &Context<Self>,
+        tech: Tech,
+        msg: fn() -> Msg,
+        disabled: bool,
+    ) -> Html {
+        let tech_deck = &ctx.props().tech_deck;
+        if tech_deck.owned().contains(&tech) {
+            html! {
+                <button
+                    class="dice-roller-button"
+                    onclick={ctx.link().callback(move |_| msg())}
+                    disabled={disabled || tech_deck.is_exhausted(&tech)}
+                >
+                    { tech.name() }
+                </button>
+            }
+        } else {
+            html! {}
+        }
+    }
+
+    fn save_roller(&self, props: &Props) {
+        if LocalStorage::set(props.storage_key(TASK_ROLLER_KEY), &self.roller).is_err() {
+            log::error!("Error saving task roller");
+        }
+    }
+}
+
+impl Component for DiceRoller {
+    type Message = Msg;
+
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let roller: TaskRoller =
+            LocalStorage::get(ctx.props().storage_key(TASK_ROLLER_KEY)).unwrap_or_default();
+        // Carry on with the task that was last rolled for, if it's one of the options
+        let selected_task_index = ctx
+            .props()
+            .tasks
+            .iter()
+            .position(|option| roller.task() == Some(&option.task))
+            .unwrap_or(0);
+        let success_dice = ctx
+            .props()
+            .tasks
+            .get(selected_task_index)
+            .map(|option| option.success_dice)
+            .unwrap_or(0);
+        Self {
+            open: roller.task().is_some(),
+            roller,
+            selected_task_index,
+            success_dice,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Toggle => {
+                self.open = !self.open;
+                true
+            }
+            Msg::SelectTask(index) => {
+                if let Some(option) = ctx.props().tasks.get(index) {
+                    self.selected_task_index = index;
+                    self.success_dice = option.success_dice;
+                }
+                true
+            }
+            Msg::IncreaseSuccessDice => {
+                self.success_dice += 1;
+                true
+            }
+            Msg::DecreaseSuccessDice => {
+                if self.success_dice > 0 {
+                    self.success_dice -= 1;
+                }
+                true
+            }
+            Msg::UseAlienIntel => {
+                if let Some(option) = self.selected_task(ctx.props()) {
+                    let task = option.task.clone();
+                    let mut tech_deck = ctx.props().tech_deck.clone();
+                    if self.roller.use_alien_intel(&task, &mut tech_deck) {
+                        ctx.props().on_use_tech.emit(Tech::AlienIntel);
+                    }
+                    self.save_roller(ctx.props());
+                }
+                true
+            }
+            Msg::Roll => {
+                if let Some(option) = self.selected_task(ctx.props()) {
+                    let task = option.task.clone();
+                    self.roller
+                        .roll(&task, self.success_dice, &mut rand::thread_rng());
+                    self.save_roller(ctx.props());
+                }
+                true
+            }
+            Msg::UseCarapaceArmour => {
+                let mut tech_deck = ctx.props().tech_deck.clone();
+                if self
+                    .roller
+                    .reroll_alien_die(&mut tech_deck, &mut rand::thread_rng())
+                {
+                    ctx.props().on_use_tech.emit(Tech::CarapaceArmour);
+                }
+                self.save_roller(ctx.props());
+                true
+            }
+            Msg::UseEMPCannon => {
+                // Successes can't be rerolled, so only the failed dice are worth rolling again
+                let failed_dice = self
+                    .roller
+                    .last_roll()
+                    .map(|roll| {
+                        roll.success_dice
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, die)| **die < SUCCESS_DIE_THRESHOLD)
+                            .map(|(index, _)| index)
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                let mut tech_deck = ctx.props().tech_deck.clone();
+                if self.roller.reroll_success_dice(
+                    &failed_dice,
+                    &mut tech_deck,
+                    &mut rand::thread_rng(),
+                ) {
+                    ctx.props().on_use_tech.emit(Tech::EMPCannon);
+                }
+                self.save_roller(ctx.props());
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if !self.open {
+            return html! {
+                <div class="dice-roller">
+                    <button class="dice-roller-toggle" onclick={ctx.link().callback(|_| Msg::Toggle)}>{ "Roll dice in the app" }</button>
+                </div>
+            };
+        }
+        let selected_task = self.selected_task(ctx.props()).map(|option| &option.task);
+        // The threat only carries on while rolling for the same task
+        let (alien_threat, last_roll) =
+            if selected_task.is_some() && self.roller.task() == selected_task {
+                (self.roller.alien_threat(), self.roller.last_roll())
+            } else {
+                (MIN_ALIEN_THREAT, None)
+            };
+        html! {
+            <div class="dice-roller">
+                <button class="dice-roller-toggle" onclick={ctx.link().callback(|_| Msg::Toggle)}>{ "Hide dice roller" }</button>
+                {
+                    if ctx.props().tasks.len() > 1 {
+                        html! {
+                            <div class="die-input-container">
+                                {
+                                    ctx.props()
+                                        .tasks
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(index, option)| html! {
+                                            <button
+                                                class={classes!("die-input-button", (index == self.selected_task_index).as_some("selected"))}
+                                                onclick={ctx.link().callback(move |_| Msg::SelectTask(index))}
+                                            >
+                                                { &option.task }
+                                            </button>
+                                        })
+                                        .collect::<Html>()
+                                }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                <div class="ufo-input-container">
+                    <img class="ufo-input-continent-icon" src="assets/icons/success.png"/>
+                    <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::DecreaseSuccessDice)} disabled={self.success_dice < 1}>{"-"}</button>
+                    <span class="ufo-input-text" >{ self.success_dice }</span>
+                    <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::IncreaseSuccessDice)}>{"+"}</button>
+                </div>
+                <div class="dice-roller-threat">
+                    {inline_icon_text_phrase("alien", &format!("Alien Threat: {}/{}", alien_threat, MAX_ALIEN_THREAT))}
+                </div>
+                <div class="dice-roller-buttons">
+                    {self.tech_button_html(ctx, Tech::AlienIntel, || Msg::UseAlienIntel, alien_threat <= MIN_ALIEN_THREAT)}
+                    <button class="dice-roller-button" onclick={ctx.link().callback(|_| Msg::Roll)} disabled={selected_task.is_none()}>{ "Roll" }</button>
+                </div>
+                {
+                    match last_roll {
+                        Some(roll) => html! {
+                            <>
+                                <div class="dice-roller-dice">
+                                    {
+                                        roll.success_dice
+                                            .iter()
+                                            .map(|die| html! {
+                                                <span class={classes!("dice-roller-die", (*die >= SUCCESS_DIE_THRESHOLD).as_some("success"))}>{ die }</span>
+                                            })
+                                            .collect::<Html>()
+                                    }
+                                    <span class={classes!("dice-roller-die", "alien-die", roll.alien_threat_triggered().as_some("triggered"))}>{ roll.alien_die }</span>
+                                </div>
+                                <div class="dice-roller-result">
+                                    {inline_icon_text_phrase("success", &format!("{} Success{}", roll.successes(), if roll.successes() == 1 { "" } else { "es" }))}
+                                </div>
+                                {
+                                    if roll.alien_threat_triggered() {
+                                        html! {
+                                            <div class="dice-roller-result">
+                                                {inline_icon_text_phrase("alien", &format!("Alien Threat! The Alien Die rolled {}, at or below the threat of {}.", roll.alien_die, roll.alien_threat))}
+                                            </div>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                <div class="dice-roller-buttons">
+                                    {self.tech_button_html(ctx, Tech::CarapaceArmour, || Msg::UseCarapaceArmour, false)}
+                                    {self.tech_button_html(ctx, Tech::EMPCannon, || Msg::UseEMPCannon, roll.successes() as usize == roll.success_dice.len())}
+                                </div>
+                            </>
+                        },
+                        None => html! {},
+                    }
+                }
+            </div>
+        }
+    }
+}
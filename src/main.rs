@@ -1,81 +1,132 @@
 mod common;
+mod dice_roller;
 mod prepare_for_timed_phase;
+mod replay;
 mod resolution_phase;
 mod rules;
+mod save;
+mod save_slots;
+mod settings;
 mod tech_reference;
 mod timed_phase;
+mod ufo_map;
 
-use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use std::collections::VecDeque;
+
+use boolinator::Boolinator;
+use gloo_storage::errors::StorageError;
 use prepare_for_timed_phase::PrepareForTimedPhase;
-use rand::thread_rng;
+use replay::Replays;
 use resolution_phase::ResolutionPhase;
 use rules::RulesExplanation;
+use save::{LoadError, SaveSlot, SlotId};
+use save_slots::SaveSlots;
 use serde::{Deserialize, Serialize};
-use timed_phase::TimedPhase;
-use xcom_1_card::{generate_timed_phase_prompts, GameResult, PanicLevel, TimedPhasePrompt};
+use settings::{Settings, SettingsMenu};
+use timed_phase::{TimedPhase, TimedPhaseOutcome};
+use web_sys::HtmlInputElement;
+use xcom_1_card::{
+    generate_resolution_phase_prompts, random_seed, BoardState, Difficulty, Game, GameResult,
+    TimedPhasePrompt, TimerExpiryRule, ALL_DIFFICULTIES, ALL_TIMER_EXPIRY_RULES,
+};
 use yew::prelude::*;
 
-const GAMESTATE_KEY: &str = "GameState";
-const PHASE_KEY: &str = "Phase";
-
 enum Msg {
     BeginRulesExplanation,
+    BeginReplays,
+    BeginSettings,
     BeginSetup,
+    BeginLoadSlot,
+    BeginDeleteSlot,
+    UpdateSlotName(String),
+    UpdateSeed(u64),
+    UpdateDifficulty(Difficulty),
+    UpdateTimerExpiryRule(TimerExpiryRule),
+    UpdateIronman(bool),
     BeginGame,
-    ContinueGame,
+    LoadSlot(SlotId),
     EnterTimedPhase,
-    TimedPhaseCompleted,
+    TimedPhaseCompleted(TimedPhaseOutcome),
     EnterResolutionPhase,
-    AlienBaseDiscovered,
-    ResolutionPhaseCompleted {
-        panic_level: PanicLevel,
-        ufos_left: u32,
-    },
-    GameCompleted(GameResult),
+    ResolutionPhaseCompleted(BoardState),
+    GameCompleted(BoardState),
     UndoGameCompleted,
+    UndoLastStep,
     ReturnToMainMenu,
-    ClearSavedGame,
+    DeleteCurrentSlot,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-struct GameState {
-    round: u32,
-    alien_base_discovered: bool,
-    panic_level: PanicLevel,
-    ufos_left: u32,
-}
+// How many steps can be undone. Older snapshots are dropped
+const UNDO_HISTORY_LIMIT: usize = 10;
 
-impl GameState {
-    fn new() -> Self {
-        Self {
-            round: 1,
-            alien_base_discovered: false,
-            panic_level: PanicLevel::Yellow,
-            ufos_left: 0,
-        }
-    }
+// Everything needed to put the game back how it was before a step was taken, including what the
+//   phase had saved to resume mid-phase
+struct Snapshot {
+    game: Game,
+    phase: Phase,
+    phase_storage: Vec<(String, String)>,
 }
 
 struct Model {
     phase: Phase,
-    game_state: GameState,
+    game: Game,
+    slot: SaveSlot,
+    settings: Settings,
+    // Only kept while the game is open, so steps from before a reload can't be undone
+    history: VecDeque<Snapshot>,
 }
 
 impl Model {
     fn save(&self) -> Result<(), StorageError> {
-        LocalStorage::set(GAMESTATE_KEY, &self.game_state)?;
-        LocalStorage::set(PHASE_KEY, &self.phase)?;
-        Ok(())
+        save::save(&self.slot, &self.game, &self.phase)
     }
 
-    fn load() -> Result<Self, StorageError> {
-        let game_state = LocalStorage::get(GAMESTATE_KEY)?;
-        let phase = LocalStorage::get(PHASE_KEY)?;
-        Ok(Self { game_state, phase })
+    fn load(slot: SaveSlot) -> Result<Self, LoadError> {
+        let (game, phase) = save::load(slot.id)?;
+        Ok(Self {
+            game,
+            phase,
+            slot,
+            settings: Settings::load(),
+            history: VecDeque::new(),
+        })
     }
 
-    fn clear_saved_game() {
-        LocalStorage::clear()
+    // Call before a step changes the game or phase so it can be undone. Ironman games keep no
+    //   history
+    fn record_step(&mut self) {
+        if self.game.state().ironman {
+            return;
+        }
+        if self.history.len() >= UNDO_HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(Snapshot {
+            game: self.game.clone(),
+            phase: self.phase.clone(),
+            phase_storage: save::read_phase_storage(&self.slot),
+        });
+    }
+
+    // Returns false if there was nothing to undo
+    fn undo_last_step(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(snapshot) => {
+                self.game = snapshot.game;
+                self.phase = snapshot.phase;
+                save::restore_phase_storage(&self.slot, &snapshot.phase_storage);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn undo_callback(&self, ctx: &Context<Self>) -> Option<Callback<()>> {
+        (!self.history.is_empty()).as_some_from(|| ctx.link().callback(|_| Msg::UndoLastStep))
+    }
+
+    fn delete_current_slot(&self) {
+        save::delete_slot(self.slot.id);
     }
 }
 
@@ -83,6 +134,10 @@ impl Model {
 enum Phase {
     MainMenu,
     RulesExplanation,
+    Replays,
+    Settings,
+    LoadSlot,
+    DeleteSlot,
     SetUp,
     PrepareForTimedPhase,
     TimedPhase(Vec<TimedPhasePrompt>),
@@ -91,15 +146,38 @@ enum Phase {
     GameCompleted(GameResult),
 }
 
+impl Phase {
+    fn description(&self) -> &'static str {
+        match self {
+            Phase::MainMenu
+            | Phase::RulesExplanation
+            | Phase::Replays
+            | Phase::Settings
+            | Phase::LoadSlot
+            | Phase::DeleteSlot
+            | Phase::SetUp => "Setting Up",
+            Phase::PrepareForTimedPhase => "Preparing for Timed Phase",
+            Phase::TimedPhase(_) => "Timed Phase",
+            Phase::PrepareForResolutionPhase => "Preparing for Resolution Phase",
+            Phase::ResolutionPhase => "Resolution Phase",
+            Phase::GameCompleted(_) => "Game Over",
+        }
+    }
+}
+
 impl Component for Model {
     type Message = Msg;
 
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
+        save::migrate_unnamespaced_storage();
         Self {
             phase: Phase::MainMenu,
-            game_state: GameState::new(),
+            game: Game::new(random_seed(), Difficulty::default()),
+            slot: SaveSlot::new(),
+            settings: Settings::load(),
+            history: VecDeque::new(),
         }
     }
 
@@ -109,8 +187,58 @@ impl Component for Model {
                 self.phase = Phase::RulesExplanation;
                 true
             }
+            Msg::BeginReplays => {
+                self.phase = Phase::Replays;
+                true
+            }
+            Msg::BeginSettings => {
+                self.phase = Phase::Settings;
+                true
+            }
             Msg::BeginSetup => {
                 self.phase = Phase::SetUp;
+                self.slot = SaveSlot::new();
+                self.history.clear();
+                true
+            }
+            Msg::BeginLoadSlot => {
+                self.phase = Phase::LoadSlot;
+                true
+            }
+            Msg::BeginDeleteSlot => {
+                self.phase = Phase::DeleteSlot;
+                true
+            }
+            Msg::UpdateSlotName(name) => {
+                self.slot.name = name;
+                true
+            }
+            Msg::UpdateSeed(seed) => {
+                let (timer_expiry_rule, ironman) = (
+                    self.game.state().timer_expiry_rule,
+                    self.game.state().ironman,
+                );
+                self.game = Game::new(seed, self.game.state().difficulty);
+                self.game.set_timer_expiry_rule(timer_expiry_rule);
+                self.game.set_ironman(ironman);
+                true
+            }
+            Msg::UpdateDifficulty(difficulty) => {
+                let (timer_expiry_rule, ironman) = (
+                    self.game.state().timer_expiry_rule,
+                    self.game.state().ironman,
+                );
+                self.game = Game::new(self.game.state().seed, difficulty);
+                self.game.set_timer_expiry_rule(timer_expiry_rule);
+                self.game.set_ironman(ironman);
+                true
+            }
+            Msg::UpdateTimerExpiryRule(timer_expiry_rule) => {
+                self.game.set_timer_expiry_rule(timer_expiry_rule);
+                true
+            }
+            Msg::UpdateIronman(ironman) => {
+                self.game.set_ironman(ironman);
                 true
             }
             Msg::BeginGame => {
@@ -118,77 +246,106 @@ impl Component for Model {
                 true
             }
             Msg::EnterTimedPhase => {
-                let prompts = generate_timed_phase_prompts(
-                    self.game_state.round,
-                    &self.game_state.panic_level,
-                    self.game_state.ufos_left,
-                    self.game_state.round == 5,
-                    &mut thread_rng(),
-                );
-                self.phase = Phase::TimedPhase(prompts);
-                if let Err(_) = self.save() {
+                self.phase = Phase::TimedPhase(self.game.start_round(js_sys::Date::now() as u64));
+                if self.save().is_err() {
                     log::error!("Error saving game");
                 }
                 true
             }
-            Msg::AlienBaseDiscovered => {
-                self.game_state.alien_base_discovered = true;
-                false
-            }
-            Msg::TimedPhaseCompleted => {
+            Msg::TimedPhaseCompleted(outcome) => {
+                self.record_step();
+                timed_phase::clear_storage(&self.slot.storage_prefix());
+                if outcome.timer_expired {
+                    self.game.expire_timer();
+                }
+                self.game.deploy_forces(outcome.forces);
+                self.game.choose_research(outcome.tech_deck);
+                if let Phase::TimedPhase(ref prompts) = self.phase {
+                    self.game.complete_timed_phase(prompts);
+                }
                 self.phase = Phase::PrepareForResolutionPhase;
-                if let Err(_) = self.save() {
+                if self.save().is_err() {
                     log::error!("Error saving game");
                 }
                 true
             }
             Msg::EnterResolutionPhase => {
                 self.phase = Phase::ResolutionPhase;
-                if let Err(_) = self.save() {
+                if self.save().is_err() {
                     log::error!("Error saving game");
                 }
                 true
             }
-            Msg::ResolutionPhaseCompleted {
-                panic_level,
-                ufos_left,
-            } => {
+            Msg::ResolutionPhaseCompleted(board_state) => {
+                self.record_step();
+                resolution_phase::clear_storage(&self.slot.storage_prefix());
+                self.game
+                    .submit_board_state(board_state, js_sys::Date::now() as u64);
                 self.phase = Phase::PrepareForTimedPhase;
-                self.game_state = GameState {
-                    panic_level,
-                    ufos_left,
-                    round: self.game_state.round + 1,
-                    ..self.game_state
-                };
-                if let Err(_) = self.save() {
+                if self.save().is_err() {
                     log::error!("Error saving game");
                 }
                 true
             }
-            Msg::GameCompleted(result) => {
-                self.phase = Phase::GameCompleted(result);
+            Msg::GameCompleted(board_state) => {
+                self.record_step();
+                if let Some(result) = self
+                    .game
+                    .submit_board_state(board_state, js_sys::Date::now() as u64)
+                {
+                    self.phase = Phase::GameCompleted(result);
+                    if self.save().is_err() {
+                        log::error!("Error saving game");
+                    }
+                }
                 true
             }
+            Msg::UndoGameCompleted if self.game.state().ironman => false,
             Msg::UndoGameCompleted => {
-                self.phase = Phase::ResolutionPhase;
+                // Games loaded after they finished have no history, but can still be taken back
+                if !self.undo_last_step() {
+                    self.game.undo_game_result();
+                    self.phase = Phase::ResolutionPhase;
+                }
+                if self.save().is_err() {
+                    log::error!("Error saving game");
+                }
                 true
             }
+            Msg::UndoLastStep => {
+                if self.undo_last_step() {
+                    if self.save().is_err() {
+                        log::error!("Error saving game");
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
             Msg::ReturnToMainMenu => {
                 self.phase = Phase::MainMenu;
-                self.game_state = GameState::new();
+                self.game = Game::new(random_seed(), Difficulty::default());
+                self.history.clear();
+                // They may have just been changed on the settings screen
+                self.settings = Settings::load();
                 true
             }
-            Msg::ContinueGame => {
-                if let Ok(model) = Self::load() {
-                    self.phase = model.phase;
-                    self.game_state = model.game_state;
-                    true
-                } else {
-                    false
+            Msg::LoadSlot(id) => {
+                let slot = save::list_slots().into_iter().find(|slot| slot.id == id);
+                match slot.map(Self::load) {
+                    Some(Ok(model)) => {
+                        *self = model;
+                        true
+                    }
+                    Some(Err(e)) => {
+                        log::error!("Error loading game: {}", e);
+                        false
+                    }
+                    None => false,
                 }
             }
-            Msg::ClearSavedGame => {
-                Self::clear_saved_game();
+            Msg::DeleteCurrentSlot => {
+                self.delete_current_slot();
                 false
             }
         }
@@ -210,23 +367,61 @@ impl Component for Model {
                                     <div class="prepare-screen-text">{ "X-1C" }</div>
                                     <div class="prepare-screen-button-container">
                                         <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginRulesExplanation)}> {"Rules"}</button>
-                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().batch_callback(|_| vec![Msg::ClearSavedGame, Msg::BeginSetup])}> {"New Game"}</button>
+                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginSetup)}> {"New Game"}</button>
                                         {
-                                            if Self::load().is_ok() {
-                                                html!{
-                                                    <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::ContinueGame)}> {"Continue"}</button>
+                                            match save::list_slots().first() {
+                                                Some(slot) => {
+                                                    let id = slot.id;
+                                                    html!{
+                                                        <>
+                                                            {
+                                                                match save::load(id) {
+                                                                    Ok(_) => html!{
+                                                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(move |_| Msg::LoadSlot(id))}> {"Continue"}</button>
+                                                                    },
+                                                                    Err(e) => html!{
+                                                                        <div class="save-error-text">
+                                                                            { format!("{} It can be removed from Delete Game.", e) }
+                                                                        </div>
+                                                                    },
+                                                                }
+                                                            }
+                                                            <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginLoadSlot)}> {"Load Game"}</button>
+                                                            <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginDeleteSlot)}> {"Delete Game"}</button>
+                                                        </>
+                                                    }
                                                 }
-                                            } else {
-                                                html!{}
+                                                None => html!{},
                                             }
                                         }
+                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginReplays)}> {"Replays"}</button>
+                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginSettings)}> {"Settings"}</button>
                                     </div>
                                 </div>
                             }
                         }
                         Phase::RulesExplanation => {
                             html!{
-                                <RulesExplanation on_main_menu={ctx.link().callback(|_| Msg::ReturnToMainMenu)} on_completed={ctx.link().batch_callback(|_| vec![Msg::ClearSavedGame, Msg::BeginSetup])}/>
+                                <RulesExplanation on_main_menu={ctx.link().callback(|_| Msg::ReturnToMainMenu)} on_completed={ctx.link().callback(|_| Msg::BeginSetup)}/>
+                            }
+                        }
+                        Phase::Replays => {
+                            html!{
+                                <Replays on_main_menu={ctx.link().callback(|_| Msg::ReturnToMainMenu)}/>
+                            }
+                        }
+                        Phase::Settings => {
+                            html!{
+                                <SettingsMenu on_main_menu={ctx.link().callback(|_| Msg::ReturnToMainMenu)}/>
+                            }
+                        }
+                        Phase::LoadSlot | Phase::DeleteSlot => {
+                            html!{
+                                <SaveSlots
+                                    deleting={matches!(self.phase, Phase::DeleteSlot)}
+                                    on_load={ctx.link().callback(Msg::LoadSlot)}
+                                    on_main_menu={ctx.link().callback(|_| Msg::ReturnToMainMenu)}
+                                />
                             }
                         }
                         Phase::SetUp => {
@@ -240,6 +435,118 @@ impl Component for Model {
                                             <div class="prompt-description">
                                                 {rules::RulebookSection::SetUp.details()}
                                             </div>
+                                            <div class="board-input-title">{ "Difficulty:" }</div>
+                                            <div class="difficulty-input-container">
+                                            {
+                                                ALL_DIFFICULTIES.into_iter()
+                                                    .map(|difficulty| html!{
+                                                        <>
+                                                            <input
+                                                                class="difficulty-input-radio"
+                                                                type="radio"
+                                                                id={format!("difficulty-{}", difficulty.lowercase())}
+                                                                name="difficulty-input"
+                                                                value={difficulty.lowercase()}
+                                                                onchange={ctx.link().callback(move |_| Msg::UpdateDifficulty(difficulty))}
+                                                                checked={difficulty == self.game.state().difficulty}
+                                                            />
+                                                            <label
+                                                                class="difficulty-input-label"
+                                                                for={format!("difficulty-{}", difficulty.lowercase())}
+                                                            >
+                                                                {difficulty.to_string()}
+                                                            </label>
+                                                        </>
+                                                    })
+                                                    .collect::<Html>()
+                                            }
+                                            </div>
+                                            <div class="prompt-description">
+                                                {self.game.state().difficulty.description()}
+                                            </div>
+                                            <div class="board-input-title">{ "When the timer runs out:" }</div>
+                                            <div class="difficulty-input-container">
+                                            {
+                                                ALL_TIMER_EXPIRY_RULES.into_iter()
+                                                    .map(|timer_expiry_rule| html!{
+                                                        <>
+                                                            <input
+                                                                class="difficulty-input-radio"
+                                                                type="radio"
+                                                                id={format!("timer-expiry-{}", timer_expiry_rule.lowercase())}
+                                                                name="timer-expiry-input"
+                                                                value={timer_expiry_rule.lowercase()}
+                                                                onchange={ctx.link().callback(move |_| Msg::UpdateTimerExpiryRule(timer_expiry_rule))}
+                                                                checked={timer_expiry_rule == self.game.state().timer_expiry_rule}
+                                                            />
+                                                            <label
+                                                                class="difficulty-input-label"
+                                                                for={format!("timer-expiry-{}", timer_expiry_rule.lowercase())}
+                                                            >
+                                                                {timer_expiry_rule.to_string()}
+                                                            </label>
+                                                        </>
+                                                    })
+                                                    .collect::<Html>()
+                                            }
+                                            </div>
+                                            <div class="prompt-description">
+                                                {self.game.state().timer_expiry_rule.description()}
+                                            </div>
+                                            <div class="alien-base-destroyed-input-container">
+                                                <label for="ironman_input">{ "Ironman:" }</label>
+                                                <input
+                                                    class="alien-base-destroyed-input-checkbox"
+                                                    type="checkbox"
+                                                    name="ironman_input"
+                                                    checked={self.game.state().ironman}
+                                                    onchange={ctx.link().batch_callback(|e: Event| {
+                                                        if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
+                                                            return vec![Msg::UpdateIronman(input_element.checked())];
+                                                        }
+                                                        vec![]
+                                                    })}
+                                                />
+                                            </div>
+                                            <div class="prompt-description">
+                                                { "In an Ironman game there's no going back. Earlier prompts can't be revisited, steps can't be undone and the end of the game is final. Progress is saved after every prompt." }
+                                            </div>
+                                            <div class="slot-name-input-container">
+                                                <label for="slot_name_input">{ "Campaign Name:" }</label>
+                                                <input
+                                                    class="slot-name-input"
+                                                    type="text"
+                                                    name="slot_name_input"
+                                                    value={self.slot.name.clone()}
+                                                    onchange={ctx.link().batch_callback(|e: Event| {
+                                                        if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
+                                                            let name = input_element.value().trim().to_owned();
+                                                            if !name.is_empty() {
+                                                                return vec![Msg::UpdateSlotName(name)];
+                                                            }
+                                                        }
+                                                        vec![]
+                                                    })}
+                                                />
+                                            </div>
+                                            <div class="seed-input-container">
+                                                <label for="seed_input">{ "Invasion Seed:" }</label>
+                                                <input
+                                                    class="seed-input"
+                                                    type="text"
+                                                    inputmode="numeric"
+                                                    name="seed_input"
+                                                    value={self.game.state().seed.to_string()}
+                                                    onchange={ctx.link().batch_callback(|e: Event| {
+                                                        if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
+                                                            if let Ok(seed) = input_element.value().trim().parse::<u64>() {
+                                                                return vec![Msg::UpdateSeed(seed)];
+                                                            }
+                                                        }
+                                                        vec![]
+                                                    })}
+                                                />
+                                            </div>
                                         </div>
                                     </div>
                                     <div class="bottom-panel">
@@ -250,15 +557,31 @@ impl Component for Model {
                             }
                         }
                         Phase::PrepareForTimedPhase => {
-                            html!{<PrepareForTimedPhase countdown_time={3000.0} on_countdown_completed={ctx.link().callback(|_| Msg::EnterTimedPhase)}/>}
+                            html!{
+                                <PrepareForTimedPhase
+                                    countdown_time={self.settings.countdown_time_ms}
+                                    pause_when_unfocused={self.settings.pause_when_unfocused}
+                                    on_countdown_completed={ctx.link().callback(|_| Msg::EnterTimedPhase)}
+                                    on_undo={self.undo_callback(ctx)}
+                                />
+                            }
                         }
                         Phase::TimedPhase(ref prompts) => {
                             html! {
                                 <TimedPhase
                                     prompts={prompts.clone()}
-                                    round={self.game_state.round}
-                                    on_completed={ctx.link().callback(|_| Msg::TimedPhaseCompleted)}
-                                    on_alien_base_discovered={ctx.link().callback(|_| Msg::AlienBaseDiscovered)}
+                                    round={self.game.state().round}
+                                    starting_time_ms={self.settings.timed_phase_starting_time_ms(self.game.state().difficulty)}
+                                    time_per_prompt_ms={self.settings.timed_phase_time_per_prompt_ms(self.game.state().difficulty)}
+                                    reload_grace_ms={self.settings.reload_grace_ms}
+                                    pause_when_unfocused={self.settings.pause_when_unfocused}
+                                    forces={self.game.state().forces.clone()}
+                                    tech_deck={self.game.state().tech_deck.clone()}
+                                    timer_expiry_rule={self.game.state().timer_expiry_rule}
+                                    preview_next_prompt={self.settings.preview_next_prompt}
+                                    ironman={self.game.state().ironman}
+                                    on_completed={ctx.link().callback(Msg::TimedPhaseCompleted)}
+                                    storage_prefix={self.slot.storage_prefix()}
                                 />
                             }
                         },
@@ -267,8 +590,17 @@ impl Component for Model {
 
                                 <div class="background-image prepare-screen" style="background-image: url(assets/background-art/ufos-over-city.png)">
                                     <div class="prepare-screen-text">{ "Prepare for Resolution Phase" }</div>
+                                    {common::research_html(&self.game.state().tech_deck)}
                                     <div class="prepare-screen-button-container">
                                         <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::EnterResolutionPhase)}> {"Enter Resolution Phase"}</button>
+                                        {
+                                            match self.undo_callback(ctx) {
+                                                Some(on_undo) => html! {
+                                                    <button class="prepare-screen-button button-shadow" onclick={Callback::from(move |_| on_undo.emit(()))}> {"Undo Last Step"}</button>
+                                                },
+                                                None => html! {},
+                                            }
+                                        }
                                     </div>
                                 </div>
                             }
@@ -276,25 +608,46 @@ impl Component for Model {
                         Phase::ResolutionPhase => {
                             html! {
                                 <ResolutionPhase
-                                    panic_level={self.game_state.panic_level.clone()}
-                                    ufos_left={self.game_state.ufos_left}
-                                    alien_base_discovered={self.game_state.alien_base_discovered}
-                                    round={self.game_state.round}
-                                    on_completed={ctx.link().callback(|(panic_level, ufos_left)| Msg::ResolutionPhaseCompleted {
-                                        panic_level,
-                                        ufos_left,
-                                    })}
-                                    on_game_end={ctx.link().callback(|result| Msg::GameCompleted(result))}
+                                    prompts={generate_resolution_phase_prompts(self.game.state())}
+                                    panic={self.game.state().panic}
+                                    funds={self.game.funds()}
+                                    forces={self.game.state().forces.clone()}
+                                    tech_deck={self.game.state().tech_deck.clone()}
+                                    alien_base={self.game.state().alien_base.clone()}
+                                    round={self.game.state().round}
+                                    ironman={self.game.state().ironman}
+                                    on_completed={ctx.link().callback(Msg::ResolutionPhaseCompleted)}
+                                    on_game_end={ctx.link().callback(Msg::GameCompleted)}
+                                    on_undo={self.undo_callback(ctx)}
+                                    storage_prefix={self.slot.storage_prefix()}
                                 />
                             }
                         },
                         Phase::GameCompleted(ref result) => {
                             html!{
                                 <div class="background-image prepare-screen" style={format!("background-image: url({})", image_for_result(result))}>
-                                    <div class="prepare-screen-text">{ format!("{}", result) }</div>
+                                    <div class="prepare-screen-text">
+                                        { format!("{}", result) }
+                                        <div class="seed-text">{ format!("Seed: {}", self.game.state().seed) }</div>
+                                    </div>
                                     <div class="prepare-screen-button-container">
-                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::UndoGameCompleted)} >{ "Back" }</button>
-                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().batch_callback(|_| vec![Msg::ClearSavedGame, Msg::ReturnToMainMenu])} >{ "Quit" }</button>
+                                        {
+                                            if self.game.state().ironman {
+                                                html! {}
+                                            } else {
+                                                html! {
+                                                    <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::UndoGameCompleted)} >{ "Back" }</button>
+                                                }
+                                            }
+                                        }
+                                        <a
+                                            class="prepare-screen-button link-button button-shadow"
+                                            href={game_log_download_url(&self.game)}
+                                            download={format!("x1c-game-{}.json", self.game.state().seed)}
+                                        >
+                                            { "Download Log" }
+                                        </a>
+                                        <button class="prepare-screen-button button-shadow" onclick={ctx.link().batch_callback(|_| vec![Msg::DeleteCurrentSlot, Msg::ReturnToMainMenu])} >{ "Quit" }</button>
                                     </div>
                                 </div>
                             }
@@ -317,6 +670,20 @@ fn image_for_result(result: &GameResult) -> String {
     .to_owned()
 }
 
+// The game log as a JSON file that can be downloaded from a link without a server
+fn game_log_download_url(game: &Game) -> String {
+    match game.log().to_json() {
+        Ok(json) => format!(
+            "data:application/json;charset=utf-8,{}",
+            String::from(js_sys::encode_uri_component(&json))
+        ),
+        Err(_) => {
+            log::error!("Error exporting game log");
+            "".to_owned()
+        }
+    }
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
     yew::start_app::<Model>();
@@ -9,7 +9,11 @@ pub enum Msg {
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
     pub countdown_time: f64,
+    pub pause_when_unfocused: bool,
     pub on_countdown_completed: Callback<()>,
+    // Steps back into the previous phase, if there's anything to undo
+    #[prop_or_default]
+    pub on_undo: Option<Callback<()>>,
 }
 
 pub struct PrepareForTimedPhase {
@@ -36,7 +40,7 @@ impl Component for PrepareForTimedPhase {
             Msg::Tick => {
                 let next_tick_time = js_sys::Date::now();
                 let diff = next_tick_time - self.last_tick_time;
-                if document().has_focus().unwrap_or(false) {
+                if !ctx.props().pause_when_unfocused || document().has_focus().unwrap_or(false) {
                     self.time_remaining_ms = f64::max(self.time_remaining_ms - diff, 0.0);
                 }
                 if self.time_remaining_ms == 0.0 {
@@ -75,6 +79,14 @@ impl Component for PrepareForTimedPhase {
                     <div class="prepare-screen-text">{ "Prepare for Timed Phase" }</div>
                         <div class="prepare-screen-button-container">
                         <button class="prepare-screen-button button-shadow" onclick={ctx.link().callback(|_| Msg::BeginCountdown)}> {"Enter Timed Phase"}</button>
+                        {
+                            match ctx.props().on_undo.clone() {
+                                Some(on_undo) => html! {
+                                    <button class="prepare-screen-button button-shadow" onclick={Callback::from(move |_| on_undo.emit(()))}> {"Undo Last Step"}</button>
+                                },
+                                None => html! {},
+                            }
+                        }
                     </div>
                 </div>
             }
@@ -0,0 +1,353 @@
+use crate::common::inline_icon_text_phrase;
+use crate::save;
+use crate::timed_phase::{description_html_for_prompt, icon_html_for_prompt};
+use gloo::file::{callbacks::FileReader, File};
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use web_sys::HtmlInputElement;
+use xcom_1_card::{GameLog, RoundLogEntry, TimedPhasePrompt};
+use yew::prelude::*;
+
+pub fn finished_games_key() -> String {
+    save::namespaced_key("finished_games")
+}
+
+pub fn load_finished_games() -> Vec<GameLog> {
+    LocalStorage::get(finished_games_key()).unwrap_or_default()
+}
+
+pub fn save_finished_game(log: &GameLog) -> Result<(), StorageError> {
+    let mut finished_games = load_finished_games();
+    finished_games.push(log.clone());
+    LocalStorage::set(finished_games_key(), finished_games)
+}
+
+fn game_summary(log: &GameLog) -> String {
+    format!(
+        "{} - {} rounds - {}{} (Seed: {})",
+        log.result
+            .as_ref()
+            .map(|result| result.to_string())
+            .unwrap_or_else(|| "Unfinished".to_owned()),
+        log.rounds().len(),
+        log.difficulty,
+        if log.ironman { " Ironman" } else { "" },
+        log.seed
+    )
+}
+
+pub enum Msg {
+    SelectGame(usize),
+    CloseGame,
+    ImportFile(web_sys::File),
+    FileLoaded(Result<String, String>),
+}
+
+// Lists finished and imported games, showing the selected one in a ReplayViewer
+pub struct Replays {
+    games: Vec<GameLog>,
+    selected_game: Option<usize>,
+    file_reader: Option<FileReader>,
+    import_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ReplaysProps {
+    pub on_main_menu: Callback<()>,
+}
+
+impl Component for Replays {
+    type Message = Msg;
+    type Properties = ReplaysProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            games: load_finished_games(),
+            selected_game: None,
+            file_reader: None,
+            import_error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::SelectGame(index) => {
+                self.selected_game = Some(index);
+                true
+            }
+            Msg::CloseGame => {
+                self.selected_game = None;
+                true
+            }
+            Msg::ImportFile(file) => {
+                let link = ctx.link().clone();
+                self.file_reader = Some(gloo::file::callbacks::read_as_text(
+                    &File::from(file),
+                    move |result| {
+                        link.send_message(Msg::FileLoaded(result.map_err(|e| e.to_string())))
+                    },
+                ));
+                false
+            }
+            Msg::FileLoaded(result) => {
+                self.file_reader = None;
+                match result.map(|json| GameLog::from_json(&json)) {
+                    Ok(Ok(log)) => {
+                        if save_finished_game(&log).is_err() {
+                            log::error!("Error saving imported game");
+                        }
+                        self.games.push(log);
+                        self.selected_game = Some(self.games.len() - 1);
+                        self.import_error = None;
+                    }
+                    Ok(Err(e)) => {
+                        self.import_error = Some(format!("That file isn't a game log: {}", e));
+                    }
+                    Err(e) => {
+                        self.import_error = Some(format!("Couldn't read that file: {}", e));
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        false
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        if let Some(log) = self.selected_game.and_then(|index| self.games.get(index)) {
+            return html! {
+                <ReplayViewer log={log.clone()} on_close={ctx.link().callback(|_| Msg::CloseGame)}/>
+            };
+        }
+        html! {
+            <>
+                <h1 class="prompt-title">{ "Replays" }</h1>
+                <div class="prompt-center-area">
+                    <div class="side-buttons">
+                    </div>
+                    <div class="prompt-details">
+                        <div class="replay-list">
+                            {
+                                if self.games.is_empty() {
+                                    html! {
+                                        <div class="prompt-description">{ "Finished games will appear here." }</div>
+                                    }
+                                } else {
+                                    self.games
+                                        .iter()
+                                        .enumerate()
+                                        .rev()
+                                        .map(|(index, log)| html! {
+                                            <button class="replay-list-button" onclick={ctx.link().callback(move |_| Msg::SelectGame(index))}>
+                                                { game_summary(log) }
+                                            </button>
+                                        })
+                                        .collect::<Html>()
+                                }
+                            }
+                        </div>
+                        <div class="replay-import-container">
+                            <label for="replay_import">{ "Import Game Log:" }</label>
+                            <input
+                                type="file"
+                                accept=".json,application/json"
+                                name="replay_import"
+                                onchange={ctx.link().batch_callback(|e: Event| {
+                                    if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
+                                        if let Some(file) = input_element.files().and_then(|files| files.get(0)) {
+                                            return vec![Msg::ImportFile(file)];
+                                        }
+                                    }
+                                    vec![]
+                                })}
+                            />
+                        </div>
+                        {
+                            if let Some(ref error) = self.import_error {
+                                html! { <div class="prompt-description">{ error }</div> }
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </div>
+                </div>
+                <div class="bottom-panel">
+                    <button class="button-back" onclick={ctx.props().on_main_menu.reform(|_| ())}>{ "Main Menu" }</button>
+                </div>
+            </>
+        }
+    }
+}
+
+// Each round is replayed as its timed phase prompts followed by the board state reported at the end
+enum ReplayStep<'a> {
+    Prompt(&'a RoundLogEntry, &'a TimedPhasePrompt),
+    RoundSummary(&'a RoundLogEntry),
+}
+
+pub enum ReplayViewerMsg {
+    NextStep,
+    PreviousStep,
+}
+
+// Steps through a game log without any timer
+pub struct ReplayViewer {
+    current_step_index: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ReplayViewerProps {
+    pub log: GameLog,
+    pub on_close: Callback<()>,
+}
+
+impl ReplayViewer {
+    fn steps(log: &GameLog) -> Vec<ReplayStep<'_>> {
+        log.rounds()
+            .iter()
+            .flat_map(|entry| {
+                entry
+                    .prompts
+                    .iter()
+                    .map(move |prompt| ReplayStep::Prompt(entry, prompt))
+                    .chain(std::iter::once(ReplayStep::RoundSummary(entry)))
+            })
+            .collect()
+    }
+}
+
+impl Component for ReplayViewer {
+    type Message = ReplayViewerMsg;
+    type Properties = ReplayViewerProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            current_step_index: 0,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ReplayViewerMsg::NextStep => {
+                if self.current_step_index + 1 < Self::steps(&ctx.props().log).len() {
+                    self.current_step_index += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            ReplayViewerMsg::PreviousStep => {
+                if self.current_step_index > 0 {
+                    self.current_step_index -= 1;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        self.current_step_index = 0;
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let steps = Self::steps(&ctx.props().log);
+        let (round, title, icons_html, description) = match steps.get(self.current_step_index) {
+            Some(ReplayStep::Prompt(entry, prompt)) => (
+                Some(entry.round),
+                prompt.title(),
+                icon_html_for_prompt(prompt),
+                description_html_for_prompt(prompt),
+            ),
+            Some(ReplayStep::RoundSummary(entry)) => (
+                Some(entry.round),
+                "End of Round".to_owned(),
+                html! {
+                    <>
+                        <img class="prompt-icon" src={format!("assets/icons/panic-input-{}.png", String::from(entry.board_state.panic.level()))}/>
+                        <img class="prompt-icon" src="assets/icons/ufo.png"/>
+                    </>
+                },
+                round_summary_html(entry),
+            ),
+            None => (
+                None,
+                "Empty Game".to_owned(),
+                html! {},
+                html! { { "No rounds were completed in this game." } },
+            ),
+        };
+        html! {
+            <>
+                <h1 class="prompt-title">{ title }</h1>
+                <div class="prompt-center-area">
+                    <div class="side-buttons">
+                        <button class="button-back" onclick={ctx.props().on_close.reform(|_| ())}>{ "Close" }</button>
+                    </div>
+                    <div class="prompt-details">
+                        <div class="prompt-icons">
+                            {icons_html}
+                        </div>
+                        <div class="prompt-description">
+                            {description}
+                        </div>
+                    </div>
+                </div>
+                <div class="bottom-panel">
+                    <button class="button-back" onclick={ctx.link().callback(|_| ReplayViewerMsg::PreviousStep)} disabled={self.current_step_index < 1}>{ "Back" }</button>
+                    <div class="round">
+                        {
+                            round
+                                .map(|round| format!("Round {} of {}", round, ctx.props().log.rounds().len()))
+                                .unwrap_or_default()
+                        }
+                    </div>
+                    <button class="button-done" onclick={ctx.link().callback(|_| ReplayViewerMsg::NextStep)} disabled={self.current_step_index + 1 >= steps.len()}>{ "Next" }</button>
+                </div>
+            </>
+        }
+    }
+}
+
+fn round_summary_html(entry: &RoundLogEntry) -> Html {
+    let alien_base_status = match (
+        entry.alien_base_discovered,
+        entry.board_state.alien_base_destroyed,
+    ) {
+        (_, true) => "The Alien Base was destroyed.",
+        (true, false) => "The Alien Base survived.",
+        (false, false) => "The Alien Base had not been discovered yet.",
+    };
+    let minutes_taken = entry.completed_at_ms.saturating_sub(entry.started_at_ms) / 60_000;
+    html! {
+        <>
+            <p>
+                {inline_icon_text_phrase("panic", "Panic")}{format!(" ended the round on {}.", entry.board_state.panic)}
+            </p>
+            <p>
+                {format!("{} ", entry.board_state.total_ufos_left())}{inline_icon_text_phrase("ufo", "UFOs")}{" were left on the World Map."}
+            </p>
+            <p>
+                {inline_icon_text_phrase("alien-base", alien_base_status)}
+            </p>
+            <p>
+                {format!("The round took {} minutes to play.", minutes_taken)}
+            </p>
+            {
+                if entry.timer_expired {
+                    html! {
+                        <p>
+                            {inline_icon_text_phrase("time", "The timer ran out during the Timed Phase.")}
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+        </>
+    }
+}
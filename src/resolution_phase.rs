@@ -1,62 +1,50 @@
 use boolinator::Boolinator;
 use gloo_storage::{LocalStorage, Storage};
-use serde::{Deserialize, Serialize};
 use web_sys::{Element, HtmlInputElement};
-use xcom_1_card::{GameResult, PanicLevel, ResolutionPhasePrompt};
+use xcom_1_card::{
+    AlienBase, BoardState, Continent, Forces, FundsLedger, PanicTrack, ResolutionPhasePrompt,
+    ResolvesPrompts, RoundSpending, Task, Tech, TechDeck, UfoTracker, ALL_CONTINENTS,
+    INTERCEPTOR_PRICE, MAX_UFOS_PER_CONTINENT, SATELLITE_PRICE,
+};
 use yew::prelude::*;
 
-use crate::tech_reference::TechReference;
+use crate::dice_roller::{DiceRoller, TaskOption, TASK_ROLLER_KEY};
+use crate::tech_reference::{tech_hints_html, TechReference, TechTiming};
+use crate::ufo_map::{ufo_map_html, UFO_TRACKER_KEY};
 use crate::{
-    common::{inline_icon_text_phrase, side_buttons, Focus},
+    common::{
+        forces_html, inline_icon_text_phrase, panic_track_html, research_html, side_buttons, Focus,
+    },
     rules::rules_reference,
 };
 
-const LATEST_PROMPT_INDEX_KEY: &str = "ResolutionPhase_LatestPromptIndex";
-const PANIC_LEVEL_INPUT_KEY: &str = "ResolutionPhase_PanicLevelInput";
+// The prompt itself is stored rather than its position, as the prompts vary between rounds and
+//   their order has changed between versions
+const LATEST_PROMPT_KEY: &str = "ResolutionPhase_LatestPrompt";
+const LEGACY_LATEST_PROMPT_INDEX_KEY: &str = "ResolutionPhase_LatestPromptIndex";
+const ADDITIONAL_PANIC_INPUT_KEY: &str = "ResolutionPhase_AdditionalPanicInput";
 const UFOS_INPUT_KEY: &str = "ResolutionPhase_UFOsInput";
-const ALIEN_BASE_DESTROYED_INPUT_KEY: &str = "ResolutionPhase_AlienBaseDestroyedInput";
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum PanicLevelInput {
-    PanicLevel(PanicLevel),
-    AlienSpace,
-}
-
-impl Into<String> for PanicLevelInput {
-    fn into(self) -> String {
-        match self {
-            PanicLevelInput::PanicLevel(PanicLevel::Yellow) => "yellow",
-            PanicLevelInput::PanicLevel(PanicLevel::Orange) => "orange",
-            PanicLevelInput::PanicLevel(PanicLevel::Red) => "red",
-            PanicLevelInput::AlienSpace => "alien",
-        }
-        .to_owned()
-    }
-}
-
-pub struct InvalidPanicLevelString;
-
-impl TryFrom<&str> for PanicLevelInput {
-    type Error = InvalidPanicLevelString;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "yellow" => Ok(PanicLevelInput::PanicLevel(PanicLevel::Yellow)),
-            "orange" => Ok(PanicLevelInput::PanicLevel(PanicLevel::Orange)),
-            "red" => Ok(PanicLevelInput::PanicLevel(PanicLevel::Red)),
-            "alien" => Ok(PanicLevelInput::AlienSpace),
-            _ => Err(InvalidPanicLevelString),
-        }
-    }
-}
+const ALIEN_BASE_DAMAGE_INPUT_KEY: &str = "ResolutionPhase_AlienBaseDamageInput";
+const SPENDING_INPUT_KEY: &str = "ResolutionPhase_SpendingInput";
+const INTERCEPTORS_LOST_INPUT_KEY: &str = "ResolutionPhase_InterceptorsLostInput";
+const RESEARCH_SUCCESSES_INPUT_KEY: &str = "ResolutionPhase_ResearchSuccessesInput";
+const RESEARCH_DESTROYED_INPUT_KEY: &str = "ResolutionPhase_ResearchDestroyedInput";
+const TECHS_USED_INPUT_KEY: &str = "ResolutionPhase_TechsUsedInput";
 
 pub struct ResolutionPhase {
-    prompts: Vec<ResolutionPhasePrompt>,
     current_prompt_index: usize,
     latest_prompt_index: usize,
-    panic_level_input: PanicLevelInput,
-    ufos_left_input: u32,
-    alien_base_destroyed_input: bool,
+    // Panic from anything other than UFOs left on the map and unpaid spending
+    additional_panic_input: u32,
+    spending_input: RoundSpending,
+    ufos_left_input: [u32; 3],
+    alien_base_damage_input: u32,
+    interceptors_lost_input: [u32; 3],
+    research_successes_input: u32,
+    research_destroyed_input: bool,
+    techs_used_input: Vec<Tech>,
+    // UFO dice as they were at the end of the timed phase, if the player entered them
+    ufo_tracker: Option<UfoTracker>,
     focus: Focus,
     prompt_details_ref: NodeRef,
 }
@@ -64,23 +52,160 @@ pub struct ResolutionPhase {
 pub enum Msg {
     NextPrompt,
     PreviousPrompt,
-    UpdatePanicLevel(PanicLevelInput),
-    IncreaseUFOsLeft,
-    DecreaseUFOsLeft,
-    UpdateAlienBaseDestroyed(bool),
+    IncreaseAdditionalPanic,
+    DecreaseAdditionalPanic,
+    IncreaseUFOsLeft(Continent),
+    DecreaseUFOsLeft(Continent),
+    IncreaseAlienBaseDamage,
+    DecreaseAlienBaseDamage,
+    IncreaseInterceptorsLost(Continent),
+    DecreaseInterceptorsLost(Continent),
+    IncreaseResearchSuccesses,
+    DecreaseResearchSuccesses,
+    UpdateResearchDestroyed(bool),
+    UseTech(Tech),
+    IncreaseSpending(SpendingInput),
+    DecreaseSpending(SpendingInput),
+    UpdateEleriumGeneratorUsed(bool),
     CheckGameEnd,
     ToggleTech,
     ToggleResearch,
 }
 
+#[derive(Clone, Copy)]
+pub enum SpendingInput {
+    InterceptorsDeployed,
+    ResearchBudget,
+    InterceptorsPurchased,
+    SatellitesPurchased,
+}
+
+impl SpendingInput {
+    fn value_mut(self, spending: &mut RoundSpending) -> &mut u32 {
+        match self {
+            Self::InterceptorsDeployed => &mut spending.interceptors_deployed,
+            Self::ResearchBudget => &mut spending.research_budget,
+            Self::InterceptorsPurchased => &mut spending.interceptors_purchased,
+            Self::SatellitesPurchased => &mut spending.satellites_purchased,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct Props {
-    pub panic_level: PanicLevel,
-    pub ufos_left: u32,
-    pub alien_base_discovered: bool,
+    // Generated from the game state at the start of the phase, so steps that can't apply this
+    //   round are left out
+    pub prompts: Vec<ResolutionPhasePrompt>,
+    pub panic: PanicTrack,
+    // Funds at the start of the resolution phase, after this round's income
+    pub funds: FundsLedger,
+    // Forces as they were deployed at the end of the timed phase
+    pub forces: Forces,
+    pub tech_deck: TechDeck,
+    pub alien_base: Option<AlienBase>,
     pub round: u32,
-    pub on_completed: Callback<(PanicLevel, u32)>,
-    pub on_game_end: Callback<GameResult>,
+    // Ironman games can't go back to earlier prompts
+    pub ironman: bool,
+    pub on_completed: Callback<BoardState>,
+    pub on_game_end: Callback<BoardState>,
+    // Steps back out of the phase from the first prompt, if there's anything to undo
+    #[prop_or_default]
+    pub on_undo: Option<Callback<()>>,
+    pub storage_prefix: String,
+}
+
+impl Props {
+    fn storage_key(&self, key: &str) -> String {
+        format!("{}{}", self.storage_prefix, key)
+    }
+}
+
+// Removes what the resolution phase saved to resume after a reload, including the UFO tracker
+//   handed over from the timed phase. Left to the caller once the phase is completed, so it can be
+//   kept for undoing
+pub fn clear_storage(storage_prefix: &str) {
+    for key in [
+        LATEST_PROMPT_KEY,
+        LEGACY_LATEST_PROMPT_INDEX_KEY,
+        ADDITIONAL_PANIC_INPUT_KEY,
+        UFOS_INPUT_KEY,
+        SPENDING_INPUT_KEY,
+        INTERCEPTORS_LOST_INPUT_KEY,
+        TASK_ROLLER_KEY,
+        RESEARCH_SUCCESSES_INPUT_KEY,
+        RESEARCH_DESTROYED_INPUT_KEY,
+        TECHS_USED_INPUT_KEY,
+        ALIEN_BASE_DAMAGE_INPUT_KEY,
+        UFO_TRACKER_KEY,
+    ] {
+        LocalStorage::delete(format!("{}{}", storage_prefix, key));
+    }
+}
+
+impl ResolutionPhase {
+    fn board_state(&self, props: &Props) -> BoardState {
+        let mut board_state = BoardState {
+            panic: props.panic,
+            ufos_left: self.ufos_left_input,
+            alien_base_destroyed: matches!(
+                props.alien_base,
+                Some(ref alien_base) if self.alien_base_damage_input >= alien_base.remaining_health()
+            ),
+            alien_base_damage: self.alien_base_damage_input,
+            spending: self.spending_input.clone(),
+            interceptors_lost: self.interceptors_lost_input,
+            research_successes: self.research_successes_input,
+            research_destroyed: self.research_destroyed_input,
+            techs_used: self.techs_used_input.clone(),
+        };
+        board_state.panic.increase(
+            board_state.continents_increasing_panic().len() as u32
+                + props.funds.audit(&self.spending_input).unpaid
+                + self.additional_panic_input,
+        );
+        board_state
+    }
+
+    // What the funds will be once the round is resolved, with any purchases made so far
+    fn funds_after_round(&self, props: &Props) -> FundsLedger {
+        let mut funds = props.funds.clone();
+        funds.resolve_round(props.round, &self.board_state(props));
+        funds
+    }
+
+    // Forces with the changes from every prompt up to and including the current one
+    fn current_forces(&self, props: &Props) -> Forces {
+        let board_state = self.board_state(props);
+        let mut forces = props.forces.clone();
+        for prompt in props.prompts[..=self.current_prompt_index].iter() {
+            forces.resolve_prompt(prompt, &board_state);
+        }
+        forces
+    }
+
+    fn current_tech_deck(&self, props: &Props) -> TechDeck {
+        let board_state = self.board_state(props);
+        let mut tech_deck = props.tech_deck.clone();
+        for tech in board_state.techs_used.iter() {
+            tech_deck.use_tech(tech);
+        }
+        for prompt in props.prompts[..=self.current_prompt_index].iter() {
+            tech_deck.resolve_prompt(prompt, &board_state);
+        }
+        tech_deck
+    }
+
+    fn forces_after_round(&self, props: &Props) -> Forces {
+        let mut forces = props.forces.clone();
+        forces.resolve_round(&self.board_state(props));
+        forces
+    }
+
+    fn save_spending_input(&self, props: &Props) {
+        if LocalStorage::set(props.storage_key(SPENDING_INPUT_KEY), &self.spending_input).is_err() {
+            log::error!("Error saving spending input");
+        }
+    }
 }
 
 impl Component for ResolutionPhase {
@@ -90,20 +215,60 @@ impl Component for ResolutionPhase {
 
     fn create(ctx: &Context<Self>) -> Self {
         // Load when component is created
-        let latest_prompt_index = LocalStorage::get(LATEST_PROMPT_INDEX_KEY).unwrap_or(0);
-        let panic_level_input = LocalStorage::get(PANIC_LEVEL_INPUT_KEY)
-            .unwrap_or(PanicLevelInput::PanicLevel(ctx.props().panic_level.clone()));
-        let ufos_left_input = LocalStorage::get(UFOS_INPUT_KEY).unwrap_or(ctx.props().ufos_left);
-        let alien_base_destroyed_input =
-            LocalStorage::get(ALIEN_BASE_DESTROYED_INPUT_KEY).unwrap_or(false);
+        let latest_prompt: Option<ResolutionPhasePrompt> =
+            LocalStorage::get(ctx.props().storage_key(LATEST_PROMPT_KEY)).ok();
+        let latest_prompt_index = latest_prompt
+            .and_then(|latest_prompt| {
+                ctx.props()
+                    .prompts
+                    .iter()
+                    .position(|prompt| *prompt == latest_prompt)
+            })
+            .unwrap_or(0);
+        let additional_panic_input =
+            LocalStorage::get(ctx.props().storage_key(ADDITIONAL_PANIC_INPUT_KEY)).unwrap_or(0);
+        let ufo_tracker: Option<UfoTracker> =
+            LocalStorage::get(ctx.props().storage_key(UFO_TRACKER_KEY)).ok();
+        let ufos_left_input = LocalStorage::get(ctx.props().storage_key(UFOS_INPUT_KEY))
+            .unwrap_or_else(|_| {
+                ALL_CONTINENTS.map(|continent| {
+                    ufo_tracker
+                        .as_ref()
+                        .and_then(|ufo_tracker| ufo_tracker.ufos(&continent))
+                        .unwrap_or(0)
+                })
+            });
+        let alien_base_damage_input =
+            LocalStorage::get(ctx.props().storage_key(ALIEN_BASE_DAMAGE_INPUT_KEY)).unwrap_or(0);
+        let spending_input = LocalStorage::get(ctx.props().storage_key(SPENDING_INPUT_KEY))
+            .unwrap_or_else(|_| RoundSpending {
+                elerium_generator_used: ctx.props().tech_deck.is_exhausted(&Tech::EleriumGenerator),
+                interceptors_deployed: ctx.props().forces.total_deployed(),
+                ..RoundSpending::default()
+            });
+        let interceptors_lost_input =
+            LocalStorage::get(ctx.props().storage_key(INTERCEPTORS_LOST_INPUT_KEY))
+                .unwrap_or([0; 3]);
+        let research_successes_input =
+            LocalStorage::get(ctx.props().storage_key(RESEARCH_SUCCESSES_INPUT_KEY)).unwrap_or(0);
+        let research_destroyed_input =
+            LocalStorage::get(ctx.props().storage_key(RESEARCH_DESTROYED_INPUT_KEY))
+                .unwrap_or(false);
+        let techs_used_input =
+            LocalStorage::get(ctx.props().storage_key(TECHS_USED_INPUT_KEY)).unwrap_or_default();
 
         Self {
-            prompts: ResolutionPhasePrompt::all(),
             current_prompt_index: latest_prompt_index,
             latest_prompt_index,
-            panic_level_input,
+            additional_panic_input,
+            spending_input,
             ufos_left_input,
-            alien_base_destroyed_input,
+            alien_base_damage_input,
+            interceptors_lost_input,
+            research_successes_input,
+            research_destroyed_input,
+            techs_used_input,
+            ufo_tracker,
             focus: Focus::Prompt,
             prompt_details_ref: NodeRef::default(),
         }
@@ -112,14 +277,17 @@ impl Component for ResolutionPhase {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::NextPrompt => {
-                if self.current_prompt_index + 1 < self.prompts.len() {
+                if self.current_prompt_index + 1 < ctx.props().prompts.len() {
                     self.current_prompt_index += 1;
                     if self.current_prompt_index > self.latest_prompt_index {
                         self.latest_prompt_index = self.current_prompt_index;
-                        if let Err(_) =
-                            LocalStorage::set(LATEST_PROMPT_INDEX_KEY, self.latest_prompt_index)
+                        if LocalStorage::set(
+                            ctx.props().storage_key(LATEST_PROMPT_KEY),
+                            &ctx.props().prompts[self.latest_prompt_index],
+                        )
+                        .is_err()
                         {
-                            log::error!("Error saving latest prompt index");
+                            log::error!("Error saving latest prompt");
                         }
                     }
                     if let Some(element) = self.prompt_details_ref.cast::<Element>() {
@@ -127,12 +295,9 @@ impl Component for ResolutionPhase {
                     }
                     true
                 } else {
-                    if let PanicLevelInput::PanicLevel(level) = self.panic_level_input.clone() {
-                        LocalStorage::delete(LATEST_PROMPT_INDEX_KEY);
-                        LocalStorage::delete(PANIC_LEVEL_INPUT_KEY);
-                        LocalStorage::delete(UFOS_INPUT_KEY);
-                        LocalStorage::delete(ALIEN_BASE_DESTROYED_INPUT_KEY);
-                        ctx.props().on_completed.emit((level, self.ufos_left_input));
+                    let board_state = self.board_state(ctx.props());
+                    if !board_state.panic.is_alien_space() {
+                        ctx.props().on_completed.emit(board_state);
                         false
                     } else {
                         false
@@ -140,7 +305,7 @@ impl Component for ResolutionPhase {
                 }
             }
             Msg::PreviousPrompt => {
-                if self.current_prompt_index > 0 {
+                if self.current_prompt_index > 0 && !ctx.props().ironman {
                     self.current_prompt_index -= 1;
                     if let Some(element) = self.prompt_details_ref.cast::<Element>() {
                         element.scroll_to_with_x_and_y(0.0, 0.0);
@@ -150,63 +315,227 @@ impl Component for ResolutionPhase {
                     false
                 }
             }
-            Msg::UpdatePanicLevel(panic_level_input) => {
-                self.panic_level_input = panic_level_input;
-                if let Err(_) =
-                    LocalStorage::set(PANIC_LEVEL_INPUT_KEY, self.panic_level_input.clone())
+            Msg::IncreaseAdditionalPanic => {
+                if !self.board_state(ctx.props()).panic.is_alien_space() {
+                    self.additional_panic_input += 1;
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(ADDITIONAL_PANIC_INPUT_KEY),
+                    self.additional_panic_input,
+                )
+                .is_err()
                 {
-                    log::error!("Error saving panic level input");
+                    log::error!("Error saving additional panic input");
                 }
-                false
+                true
             }
-            Msg::IncreaseUFOsLeft => {
-                if self.ufos_left_input < 18 {
-                    self.ufos_left_input += 1;
+            Msg::DecreaseAdditionalPanic => {
+                if self.additional_panic_input > 0 {
+                    self.additional_panic_input -= 1;
                 }
-                if let Err(_) = LocalStorage::set(UFOS_INPUT_KEY, self.ufos_left_input) {
-                    log::error!("Error saving UFOs left input");
+                if LocalStorage::set(
+                    ctx.props().storage_key(ADDITIONAL_PANIC_INPUT_KEY),
+                    self.additional_panic_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving additional panic input");
                 }
                 true
             }
-            Msg::DecreaseUFOsLeft => {
-                if self.ufos_left_input > 0 {
-                    self.ufos_left_input -= 1;
+            Msg::IncreaseUFOsLeft(continent) => {
+                let ufos_left = &mut self.ufos_left_input[continent.index()];
+                if *ufos_left < MAX_UFOS_PER_CONTINENT {
+                    *ufos_left += 1;
                 }
-                if let Err(_) = LocalStorage::set(UFOS_INPUT_KEY, self.ufos_left_input) {
+                if LocalStorage::set(
+                    ctx.props().storage_key(UFOS_INPUT_KEY),
+                    self.ufos_left_input,
+                )
+                .is_err()
+                {
                     log::error!("Error saving UFOs left input");
                 }
                 true
             }
-            Msg::UpdateAlienBaseDestroyed(alien_base_destroyed) => {
-                self.alien_base_destroyed_input = alien_base_destroyed;
-                if let Err(_) = LocalStorage::set(
-                    ALIEN_BASE_DESTROYED_INPUT_KEY,
-                    self.alien_base_destroyed_input,
-                ) {
+            Msg::DecreaseUFOsLeft(continent) => {
+                let ufos_left = &mut self.ufos_left_input[continent.index()];
+                if *ufos_left > 0 {
+                    *ufos_left -= 1;
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(UFOS_INPUT_KEY),
+                    self.ufos_left_input,
+                )
+                .is_err()
+                {
                     log::error!("Error saving UFOs left input");
                 }
-                false
+                true
+            }
+            Msg::IncreaseAlienBaseDamage => {
+                if let Some(ref alien_base) = ctx.props().alien_base {
+                    if self.alien_base_damage_input < alien_base.remaining_health() {
+                        self.alien_base_damage_input += 1;
+                    }
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(ALIEN_BASE_DAMAGE_INPUT_KEY),
+                    self.alien_base_damage_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving alien base damage input");
+                }
+                true
+            }
+            Msg::DecreaseAlienBaseDamage => {
+                if self.alien_base_damage_input > 0 {
+                    self.alien_base_damage_input -= 1;
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(ALIEN_BASE_DAMAGE_INPUT_KEY),
+                    self.alien_base_damage_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving alien base damage input");
+                }
+                true
+            }
+            Msg::IncreaseInterceptorsLost(continent) => {
+                let interceptors_lost = &mut self.interceptors_lost_input[continent.index()];
+                if *interceptors_lost < ctx.props().forces.deployed_on(&continent) {
+                    *interceptors_lost += 1;
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(INTERCEPTORS_LOST_INPUT_KEY),
+                    self.interceptors_lost_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving interceptors lost input");
+                }
+                true
+            }
+            Msg::DecreaseInterceptorsLost(continent) => {
+                let interceptors_lost = &mut self.interceptors_lost_input[continent.index()];
+                if *interceptors_lost > 0 {
+                    *interceptors_lost -= 1;
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(INTERCEPTORS_LOST_INPUT_KEY),
+                    self.interceptors_lost_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving interceptors lost input");
+                }
+                true
+            }
+            Msg::IncreaseResearchSuccesses => {
+                self.research_successes_input += 1;
+                if LocalStorage::set(
+                    ctx.props().storage_key(RESEARCH_SUCCESSES_INPUT_KEY),
+                    self.research_successes_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving research successes input");
+                }
+                true
+            }
+            Msg::DecreaseResearchSuccesses => {
+                if self.research_successes_input > 0 {
+                    self.research_successes_input -= 1;
+                }
+                if LocalStorage::set(
+                    ctx.props().storage_key(RESEARCH_SUCCESSES_INPUT_KEY),
+                    self.research_successes_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving research successes input");
+                }
+                true
+            }
+            Msg::UpdateResearchDestroyed(research_destroyed) => {
+                self.research_destroyed_input = research_destroyed;
+                if LocalStorage::set(
+                    ctx.props().storage_key(RESEARCH_DESTROYED_INPUT_KEY),
+                    self.research_destroyed_input,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving research destroyed input");
+                }
+                true
+            }
+            Msg::UseTech(tech) => {
+                let can_use = self.current_tech_deck(ctx.props()).use_tech(&tech);
+                if can_use {
+                    self.techs_used_input.push(tech);
+                    if LocalStorage::set(
+                        ctx.props().storage_key(TECHS_USED_INPUT_KEY),
+                        &self.techs_used_input,
+                    )
+                    .is_err()
+                    {
+                        log::error!("Error saving techs used input");
+                    }
+                }
+                can_use
             }
+            Msg::IncreaseSpending(input) => {
+                let can_increase = match input {
+                    SpendingInput::InterceptorsPurchased => {
+                        self.funds_after_round(ctx.props()).balance() >= INTERCEPTOR_PRICE
+                    }
+                    SpendingInput::SatellitesPurchased => {
+                        self.funds_after_round(ctx.props()).balance() >= SATELLITE_PRICE
+                            && self.forces_after_round(ctx.props()).satellite_space() > 0
+                    }
+                    _ => true,
+                };
+                if can_increase {
+                    *input.value_mut(&mut self.spending_input) += 1;
+                    self.save_spending_input(ctx.props());
+                }
+                can_increase
+            }
+            Msg::DecreaseSpending(input) => {
+                let value = input.value_mut(&mut self.spending_input);
+                if *value > 0 {
+                    *value -= 1;
+                }
+                self.save_spending_input(ctx.props());
+                true
+            }
+            // Only owners of the Elerium Generator can use it for the extra funds
+            Msg::UpdateEleriumGeneratorUsed(elerium_generator_used)
+                if ctx
+                    .props()
+                    .tech_deck
+                    .owned()
+                    .contains(&Tech::EleriumGenerator) =>
+            {
+                self.spending_input.elerium_generator_used = elerium_generator_used;
+                self.save_spending_input(ctx.props());
+                true
+            }
+            Msg::UpdateEleriumGeneratorUsed(_) => false,
             Msg::CheckGameEnd => {
-                if let Some(game_result) = match (
-                    self.alien_base_destroyed_input,
-                    self.panic_level_input.clone(),
-                ) {
-                    (true, PanicLevelInput::PanicLevel(_)) => Some(GameResult::Victory),
-                    (true, PanicLevelInput::AlienSpace) => Some(GameResult::PyrrhicVictory),
-                    (false, PanicLevelInput::AlienSpace) => Some(GameResult::Defeat),
-                    (false, PanicLevelInput::PanicLevel(_)) => None,
-                } {
-                    if let Some(input_index) = self
-                        .prompts
-                        .iter()
-                        .position(|prompt| *prompt == ResolutionPhasePrompt::AskForBoardState)
+                let board_state = self.board_state(ctx.props());
+                if board_state.game_result().is_some() {
+                    if LocalStorage::set(
+                        ctx.props().storage_key(LATEST_PROMPT_KEY),
+                        ResolutionPhasePrompt::IncreasePanic,
+                    )
+                    .is_err()
                     {
-                        if let Err(_) = LocalStorage::set(LATEST_PROMPT_INDEX_KEY, input_index) {
-                            log::error!("Error saving latest prompt index");
-                        }
+                        log::error!("Error saving latest prompt");
                     }
-                    ctx.props().on_game_end.emit(game_result);
+                    ctx.props().on_game_end.emit(board_state);
                 } else {
                     ctx.link().send_message(Msg::NextPrompt);
                 }
@@ -230,80 +559,228 @@ impl Component for ResolutionPhase {
     }
 
     fn view(&self, ctx: &Context<Self>) -> yew::Html {
-        let panic_input_change_callback = ctx.link().batch_callback(|e: Event| {
-            if let Some(element) = e.target_dyn_into::<HtmlInputElement>() {
-                if let Ok(panic_level_input) = PanicLevelInput::try_from(element.value().as_str()) {
-                    return vec![Msg::UpdatePanicLevel(panic_level_input)];
-                }
-            }
-            return vec![];
-        });
-        let prompt = &self.prompts[self.current_prompt_index];
+        let prompt = &ctx.props().prompts[self.current_prompt_index];
         let is_not_latest_prompt = self.current_prompt_index < self.latest_prompt_index;
-        let main_section = match prompt {
-            ResolutionPhasePrompt::AskForBoardState => {
-                let current_panic_level: String = self.panic_level_input.clone().into();
-                html! {
+        let board_state = self.board_state(ctx.props());
+        let prompt_input = match prompt {
+            ResolutionPhasePrompt::ResolveResearch => match ctx.props().tech_deck.researching() {
+                Some(research) => html! {
                     <div class="board-input-container">
-                        <div>
-                            <div class="board-input-title">{ "Global Panic Level:"} </div>
-                            <div class="panic-input-container">
+                        <DiceRoller
+                            key="research"
+                            tasks={vec![TaskOption { task: Task::Research, success_dice: self.spending_input.research_budget }]}
+                            tech_deck={self.current_tech_deck(ctx.props())}
+                            on_use_tech={ctx.link().callback(Msg::UseTech)}
+                            storage_prefix={ctx.props().storage_prefix.clone()}
+                        />
+                        <div class="board-input-title">{ format!("Successes for {}:", research.tech) }</div>
+                        <div class="ufo-input-container">
+                            <img class="ufo-input-continent-icon" src="assets/icons/success.png"/>
+                            <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::DecreaseResearchSuccesses)} disabled={self.research_successes_input < 1}>{"-"}</button>
+                            <span class="ufo-input-text" >{ self.research_successes_input }</span>
+                            <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::IncreaseResearchSuccesses)}>{"+"}</button>
+                        </div>
+                        <div class="alien-base-destroyed-input-container">
+                            <label for="research_destroyed_input">{ "Technology destroyed?" }</label>
+                            <input
+                                class="alien-base-destroyed-input-checkbox"
+                                type="checkbox"
+                                name="research_destroyed_input"
+                                checked={self.research_destroyed_input}
+                                onchange={ctx.link().batch_callback(move |e: Event| {
+                                    if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
+                                        return vec![Msg::UpdateResearchDestroyed(input_element.checked())];
+                                    }
+                                    vec![]
+                                })}
+                            />
+                        </div>
+                    </div>
+                },
+                None => html! {},
+            },
+            ResolutionPhasePrompt::ResolveUFODefence => {
+                let continents = ALL_CONTINENTS
+                    .iter()
+                    .filter(|continent| ctx.props().forces.deployed_on(continent) > 0)
+                    .collect::<Vec<_>>();
+                if continents.is_empty() {
+                    html! {}
+                } else {
+                    let tasks = continents
+                        .iter()
+                        .map(|continent| TaskOption {
+                            task: Task::UFODefence((*continent).clone()),
+                            success_dice: ctx.props().forces.deployed_on(continent)
+                                - self.interceptors_lost_input[continent.index()],
+                        })
+                        .collect::<Vec<_>>();
+                    html! {
+                        <div class="board-input-container">
+                            <DiceRoller
+                                key="ufo-defence"
+                                tasks={tasks}
+                                tech_deck={self.current_tech_deck(ctx.props())}
+                                on_use_tech={ctx.link().callback(Msg::UseTech)}
+                                storage_prefix={ctx.props().storage_prefix.clone()}
+                            />
+                            <div class="board-input-title">{ "Interceptors shot down:" }</div>
                             {
-                                vec!["yellow", "orange", "red", "alien"].into_iter()
-                                    .map(|input| html!{
-                                        <>
-                                            <input
-                                                class="panic-input-radio"
-                                                type="radio"
-                                                id={format!("panic-{}", input)}
-                                                name="panic-input"
-                                                value={input}
-                                                onchange={panic_input_change_callback.clone()}
-                                                checked={input == current_panic_level}
-                                            />
-                                            <label
-                                                class="panic-input-label"
-                                                for={format!("panic-{}", input)}
-                                            >
-                                                <img src={format!("assets/icons/panic-input-{}.png", input)}/>
-                                            </label>
-                                        </>
+                                continents
+                                    .into_iter()
+                                    .map(|continent| {
+                                        let interceptors_lost = self.interceptors_lost_input[continent.index()];
+                                        let decrease_continent = continent.clone();
+                                        let increase_continent = continent.clone();
+                                        html! {
+                                            <div class="ufo-input-container">
+                                                <img class="ufo-input-continent-icon" src={format!("assets/icons/{}.png", continent.lowercase())}/>
+                                                <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::DecreaseInterceptorsLost(decrease_continent.clone()))} disabled={interceptors_lost < 1}>{"-"}</button>
+                                                <span class="ufo-input-text" >{ format!("{}/{}", interceptors_lost, ctx.props().forces.deployed_on(continent)) }</span>
+                                                <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::IncreaseInterceptorsLost(increase_continent.clone()))} disabled={interceptors_lost >= ctx.props().forces.deployed_on(continent)}>{"+"}</button>
+                                            </div>
+                                        }
                                     })
                                     .collect::<Html>()
                             }
-                            </div>
                         </div>
-                        <div>
-                            <div class="board-input-title">{ "UFOs left on map:"} </div>
-                            <div class="ufo-input-container">
-                                <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::DecreaseUFOsLeft)} disabled={self.ufos_left_input < 1}>{"-"}</button>
-                                <span class="ufo-input-text" >{ self.ufos_left_input }</span>
-                                <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::IncreaseUFOsLeft)} disabled={self.ufos_left_input > 17}>{"+"}</button>
-                            </div>
+                    }
+                }
+            }
+            ResolutionPhasePrompt::AssaultAlienBase => match ctx.props().alien_base {
+                Some(ref alien_base) => html! {
+                    <div class="board-input-container">
+                        <div class="board-input-title">{ "Success Tokens added this round:" }</div>
+                        <div class="ufo-input-container">
+                            <img class="ufo-input-continent-icon" src="assets/icons/alien-base.png"/>
+                            <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::DecreaseAlienBaseDamage)} disabled={self.alien_base_damage_input < 1}>{"-"}</button>
+                            <span class="ufo-input-text" >{ format!("{}/{}", self.alien_base_damage_input, alien_base.remaining_health()) }</span>
+                            <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::IncreaseAlienBaseDamage)} disabled={self.alien_base_damage_input >= alien_base.remaining_health()}>{"+"}</button>
                         </div>
                         {
-                            if ctx.props().alien_base_discovered {
-                            html!{
-                                <div class="alien-base-destroyed-input-container">
-                                    <label for="alien_base_destroyed_input">{ "Alien Base destroyed?" }</label>
-                                    <input
-                                    class="alien-base-destroyed-input-checkbox"
-                                        type="checkbox"
-                                        name="alien_base_destroyed_input"
-                                        checked={self.alien_base_destroyed_input}
-                                        onchange={ctx.link().batch_callback(move |e: Event| {
-                                            if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
-                                                return vec![Msg::UpdateAlienBaseDestroyed(input_element.checked())];
-                                            }
-                                            return vec![];
-                                        })}
-                                    />
-                                </div>
+                            if board_state.alien_base_destroyed {
+                                html! {
+                                    <div class="funds-summary">{ "The Alien Base is destroyed!" }</div>
+                                }
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </div>
+                },
+                None => html! {},
+            },
+            _ => html! {},
+        };
+        let main_section = match prompt {
+            ResolutionPhasePrompt::AskForBoardState => html! {
+                <div class="board-input-container">
+                    {
+                        match self.ufo_tracker {
+                            Some(ref ufo_tracker) => html! {
+                                <>
+                                    <div class="ufo-map-title">{ "UFOs at the end of the Timed Phase:" }</div>
+                                    {ufo_map_html(ufo_tracker)}
+                                </>
+                            },
+                            None => html! {},
+                        }
+                    }
+                    <div class="board-input-title">{ "UFOs left on map:"} </div>
+                    {
+                        ALL_CONTINENTS
+                            .iter()
+                            .map(|continent| {
+                                let ufos_left = board_state.ufos_left_on(continent);
+                                let decrease_continent = continent.clone();
+                                let increase_continent = continent.clone();
+                                html! {
+                                    <div class="ufo-input-container">
+                                        <img class="ufo-input-continent-icon" src={format!("assets/icons/{}.png", continent.lowercase())}/>
+                                        <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::DecreaseUFOsLeft(decrease_continent.clone()))} disabled={ufos_left < 1}>{"-"}</button>
+                                        <span class="ufo-input-text" >{ ufos_left }</span>
+                                        <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::IncreaseUFOsLeft(increase_continent.clone()))} disabled={ufos_left >= MAX_UFOS_PER_CONTINENT}>{"+"}</button>
+                                    </div>
+                                }
+                            })
+                            .collect::<Html>()
+                    }
+                </div>
+            },
+            ResolutionPhasePrompt::IncreasePanic => html! {
+                <div class="board-input-container">
+                    <h1 class={classes!("prompt-title", is_not_latest_prompt.as_some("faded-text"))}>{ prompt.title() }</h1>
+                    <div class="prompt-description">
+                        {description_html_for_prompt(prompt, &board_state, ctx.props().funds.audit(&board_state.spending).unpaid, ctx.props().alien_base.as_ref())}
+                    </div>
+                    <div class="board-input-title">{ "Other panic this round:"} </div>
+                    <div class="ufo-input-container">
+                        <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::DecreaseAdditionalPanic)} disabled={self.additional_panic_input < 1}>{"-"}</button>
+                        <span class="ufo-input-text" >{ self.additional_panic_input }</span>
+                        <button class="ufo-input-button" onclick={ctx.link().callback(|_| Msg::IncreaseAdditionalPanic)} disabled={board_state.panic.is_alien_space()}>{"+"}</button>
+                    </div>
+                    <div class="board-input-title">{ "Global Panic Level:"} </div>
+                    {panic_track_html(&board_state.panic)}
+                </div>
+            },
+            ResolutionPhasePrompt::AuditSpending => {
+                let audit = ctx.props().funds.audit(&self.spending_input);
+                html! {
+                    <div class="board-input-container">
+                        <h1 class={classes!("prompt-title", is_not_latest_prompt.as_some("faded-text"))}>{ prompt.title() }</h1>
+                        <div class="prompt-description">
+                            {description_html_for_prompt(prompt, &board_state, ctx.props().funds.audit(&board_state.spending).unpaid, ctx.props().alien_base.as_ref())}
+                        </div>
+                        <div class="board-input-title">{ format!("Funds: §{}", ctx.props().funds.balance()) }</div>
+                        {
+                            if ctx.props().tech_deck.owned().contains(&Tech::EleriumGenerator) {
+                                html! {
+                                    <div class="alien-base-destroyed-input-container">
+                                        <label for="elerium_generator_input">{ "Elerium Generator used?" }</label>
+                                        <input
+                                            class="alien-base-destroyed-input-checkbox"
+                                            type="checkbox"
+                                            name="elerium_generator_input"
+                                            checked={self.spending_input.elerium_generator_used}
+                                            onchange={ctx.link().batch_callback(move |e: Event| {
+                                                if let Some(input_element) = e.target_dyn_into::<HtmlInputElement>() {
+                                                    return vec![Msg::UpdateEleriumGeneratorUsed(input_element.checked())];
+                                                }
+                                                vec![]
+                                            })}
+                                        />
+                                    </div>
                                 }
                             } else {
-                                html!{}
+                                html! {}
                             }
                         }
+                        {spending_input_html(ctx, "Interceptors deployed:", SpendingInput::InterceptorsDeployed, self.spending_input.interceptors_deployed, true)}
+                        {spending_input_html(ctx, "Research Budget:", SpendingInput::ResearchBudget, self.spending_input.research_budget, true)}
+                        <div class="funds-summary">
+                            {
+                                if audit.unpaid > 0 {
+                                    format!("Pay §{} of §{} - the Panic Track increases {} for the §{} you cannot pay.", audit.paid, audit.cost, audit.unpaid, audit.unpaid)
+                                } else {
+                                    format!("Pay §{}.", audit.paid)
+                                }
+                            }
+                        </div>
+                    </div>
+                }
+            }
+            ResolutionPhasePrompt::PurchaseReplacementForces => {
+                let remaining_funds = self.funds_after_round(ctx.props()).balance();
+                let satellite_space = self.forces_after_round(ctx.props()).satellite_space();
+                html! {
+                    <div class="board-input-container">
+                        <h1 class={classes!("prompt-title", is_not_latest_prompt.as_some("faded-text"))}>{ prompt.title() }</h1>
+                        <div class="prompt-description">
+                            {description_html_for_prompt(prompt, &board_state, ctx.props().funds.audit(&board_state.spending).unpaid, ctx.props().alien_base.as_ref())}
+                        </div>
+                        {spending_input_html(ctx, "Interceptors purchased:", SpendingInput::InterceptorsPurchased, self.spending_input.interceptors_purchased, remaining_funds >= INTERCEPTOR_PRICE)}
+                        {spending_input_html(ctx, "Satellites purchased:", SpendingInput::SatellitesPurchased, self.spending_input.satellites_purchased, remaining_funds >= SATELLITE_PRICE && satellite_space > 0)}
+                        <div class="funds-summary">{ format!("Funds remaining: §{}", remaining_funds) }</div>
                     </div>
                 }
             }
@@ -317,11 +794,13 @@ impl Component for ResolutionPhase {
                                 Focus::Prompt => html!{
                                     <div class="prompt-details" ref={self.prompt_details_ref.clone()}>
                                         <div class="prompt-icons">
-                                            {icon_html_for_prompt(&prompt)}
+                                            {icon_html_for_prompt(prompt)}
                                         </div>
                                         <div class={classes!("prompt-description", is_not_latest_prompt.as_some("faded-text"))}>
-                                            {description_html_for_prompt(&prompt, ctx.props().alien_base_discovered)}
+                                            {description_html_for_prompt(prompt, &board_state, ctx.props().funds.audit(&board_state.spending).unpaid, ctx.props().alien_base.as_ref())}
                                         </div>
+                                        {tech_hints_html(&self.current_tech_deck(ctx.props()), TechTiming::Resolution(prompt), ctx.link().callback(Msg::UseTech))}
+                                        {prompt_input}
                                     </div>
                                 },
                                 Focus::TechReference => html!{
@@ -343,16 +822,29 @@ impl Component for ResolutionPhase {
             },
         };
         let next_callback = match prompt {
-            ResolutionPhasePrompt::AskForBoardState => ctx.link().callback(|_| Msg::CheckGameEnd),
+            ResolutionPhasePrompt::IncreasePanic => ctx.link().callback(|_| Msg::CheckGameEnd),
             _ => ctx.link().callback(|_| Msg::NextPrompt),
         };
         html! {
             <>
                 {main_section}
                 <div class="bottom-panel">
-                    <button class="button-back" onclick={ctx.link().callback(|_| Msg::PreviousPrompt)} disabled={ !matches!(self.focus, Focus::Prompt) || self.current_prompt_index < 1}>{ "Back" }</button>
-                    <div class="round">
-                        {format!("Round {}", ctx.props().round)}
+                    {
+                        match ctx.props().on_undo.clone() {
+                            Some(on_undo) if self.current_prompt_index == 0 => html! {
+                                <button class="button-back" onclick={Callback::from(move |_| on_undo.emit(()))} disabled={ !matches!(self.focus, Focus::Prompt) }>{ "Undo Last Step" }</button>
+                            },
+                            _ => html! {
+                                <button class="button-back" onclick={ctx.link().callback(|_| Msg::PreviousPrompt)} disabled={ !matches!(self.focus, Focus::Prompt) || self.current_prompt_index < 1 || ctx.props().ironman }>{ "Back" }</button>
+                            },
+                        }
+                    }
+                    <div>
+                        <div class="round">
+                            {format!("Round {}", ctx.props().round)}
+                        </div>
+                        {forces_html(&self.current_forces(ctx.props()), None)}
+                        {research_html(&self.current_tech_deck(ctx.props()))}
                     </div>
                     <button class="button-done" onclick={next_callback} disabled={ !matches!(self.focus, Focus::Prompt) }>{
                         if is_not_latest_prompt {
@@ -367,6 +859,25 @@ impl Component for ResolutionPhase {
     }
 }
 
+fn spending_input_html(
+    ctx: &Context<ResolutionPhase>,
+    title: &str,
+    input: SpendingInput,
+    value: u32,
+    can_increase: bool,
+) -> Html {
+    html! {
+        <>
+            <div class="board-input-title">{ title }</div>
+            <div class="ufo-input-container">
+                <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::DecreaseSpending(input))} disabled={value < 1}>{"-"}</button>
+                <span class="ufo-input-text" >{ value }</span>
+                <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::IncreaseSpending(input))} disabled={!can_increase}>{"+"}</button>
+            </div>
+        </>
+    }
+}
+
 fn icon_html_for_prompt(prompt: &ResolutionPhasePrompt) -> Html {
     match prompt {
         ResolutionPhasePrompt::AuditSpending => html! {
@@ -378,6 +889,9 @@ fn icon_html_for_prompt(prompt: &ResolutionPhasePrompt) -> Html {
         ResolutionPhasePrompt::ResolveUFODefence => html! {
             <img class="prompt-icon" src="assets/icons/interceptor.png"/>
         },
+        ResolutionPhasePrompt::AssaultAlienBase => html! {
+            <img class="prompt-icon" src="assets/icons/alien-base.png"/>
+        },
         ResolutionPhasePrompt::IncreasePanic => html! {
             <img class="prompt-icon" src="assets/icons/alien.png"/>
         },
@@ -398,9 +912,67 @@ fn icon_html_for_prompt(prompt: &ResolutionPhasePrompt) -> Html {
     }
 }
 
+fn continent_bonus_html(continent: &Continent) -> Html {
+    let bonus = match continent {
+        Continent::America => html! {
+            <>
+                <h4 class="continent-bonus-header">{"Air and Space:"}</h4>
+                <p>
+                    {"Add one "}{inline_icon_text_phrase("interceptor", "Interceptor")}{" from the supply to your reserves."}
+                </p>
+                <p>
+                    {"Increase your number of "}{inline_icon_text_phrase("satellite", "Satellites")}{" by 1 (to a maximum of 3)."}
+                </p>
+            </>
+        },
+        Continent::Africa => html! {
+            <>
+                <h4 class="continent-bonus-header">{"All In:"}</h4>
+                <p>
+                    {"Take §2 from the supply and add it to your funds."}
+                </p>
+            </>
+        },
+        Continent::Eurasia => html! {
+            <>
+                <h4 class="continent-bonus-header">{"Expert Knowledge:"}</h4>
+                <p>
+                    {"Add one "}{inline_icon_text_phrase("success", "Success Token")}{" to the "}{inline_icon_text_phrase("tech", "Technology")}{" currently selected for research."}
+                </p>
+                <p>
+                    {"If there is no "}{inline_icon_text_phrase("tech", "Technology")}{" currently selected, draw the first token from the "}{inline_icon_text_phrase("tech", "Technology Token stack")}{" and select it, then add the "}{inline_icon_text_phrase("success", "Success Token.")}
+                </p>
+                <p>
+                    {"This may immediately complete the "}{inline_icon_text_phrase("research", "Research,")}{" granting you access to use the selected "}{inline_icon_text_phrase("tech", "Technology.")}
+                </p>
+            </>
+        },
+    };
+    html! {
+        <div class="prompt-success-outcome-container">
+            <div class="float-left">
+                <img class="icon-header" src={format!("assets/icons/{}.png", continent.lowercase())} />
+                <img class="icon-header" src={format!("assets/icons/{}-board-position.png", continent.lowercase())} />
+            </div>
+            <h2 class="continent-bonus-header">{continent}</h2>
+            {bonus}
+        </div>
+    }
+}
+
+fn continent_list_text(continents: &[Continent]) -> String {
+    continents
+        .iter()
+        .map(|continent| format!("{}", continent))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn description_html_for_prompt(
     prompt: &ResolutionPhasePrompt,
-    alien_base_discovered: bool,
+    board_state: &BoardState,
+    unpaid_spending: u32,
+    alien_base: Option<&AlienBase>,
 ) -> Html {
     match prompt {
         ResolutionPhasePrompt::AuditSpending => html! {
@@ -452,10 +1024,10 @@ fn description_html_for_prompt(
                         {"Remove one UFO from the continent."}
                     </p>
                     {
-                        if alien_base_discovered {
+                        if let Some(alien_base) = alien_base {
                             html!{
                                 <p>
-                                    {"Once all UFOs have been removed from the continent containing the "}{inline_icon_text_phrase("alien", "Alien Base,")}{" any additional "}{inline_icon_text_phrase("success", "Successes")}{" rolled in this continent's "}{inline_icon_text_phrase("interceptor", "UFO Defence Task")}{" instead add a "}{inline_icon_text_phrase("success", "Success Token")}{" on the "}{inline_icon_text_phrase("alien", "Alien Base.")}{" Once the third "}{inline_icon_text_phrase("success", "Success Token")}{" has been added to the "}{inline_icon_text_phrase("alien", "Alien Base")}{", it is destroyed!"}
+                                    {format!("Once all UFOs have been removed from {}, the continent containing the ", alien_base.continent())}{inline_icon_text_phrase("alien", "Alien Base,")}{" any additional "}{inline_icon_text_phrase("success", "Successes")}{" rolled in this continent's "}{inline_icon_text_phrase("interceptor", "UFO Defence Task")}{" instead add a "}{inline_icon_text_phrase("success", "Success Token")}{" on the "}{inline_icon_text_phrase("alien", "Alien Base.")}{" Once the third "}{inline_icon_text_phrase("success", "Success Token")}{" has been added to the "}{inline_icon_text_phrase("alien", "Alien Base")}{", it is destroyed!"}
                                 </p>
                             }
                         } else {
@@ -474,63 +1046,82 @@ fn description_html_for_prompt(
                 </div>
             </>
         },
-        ResolutionPhasePrompt::IncreasePanic => html! {
+        ResolutionPhasePrompt::IncreasePanic => {
+            let continents = board_state.continents_increasing_panic();
+            html! {
+                <>
+                    {
+                        if continents.is_empty() {
+                            html! {
+                                <p>
+                                    {"Every continent was cleared of UFOs, so they do not increase the "}{inline_icon_text_phrase("panic", "Panic Track.")}
+                                </p>
+                            }
+                        } else {
+                            html! {
+                                <p>
+                                    {"Increase the "}{inline_icon_text_phrase("panic", "Panic Track")}{format!(" {} space{} - UFOs remain in {}.", continents.len(), if continents.len() == 1 { "" } else { "s" }, continent_list_text(&continents))}
+                                </p>
+                            }
+                        }
+                    }
+                    {
+                        if unpaid_spending > 0 {
+                            html! {
+                                <p>
+                                    {"Increase the "}{inline_icon_text_phrase("panic", "Panic Track")}{format!(" {} more space{} for the §{} you could not pay during the audit.", unpaid_spending, if unpaid_spending == 1 { "" } else { "s" }, unpaid_spending)}
+                                </p>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <p>
+                        {"Add any other panic from this round below."}
+                    </p>
+                </>
+            }
+        }
+        ResolutionPhasePrompt::AssaultAlienBase => html! {
             <>
+                {
+                    match alien_base {
+                        Some(alien_base) => html! {
+                            <p>
+                                {inline_icon_text_phrase("alien-base", &format!("{}.", alien_base))}
+                            </p>
+                        },
+                        None => html! {},
+                    }
+                }
+                <p>
+                    {"Enter the "}{inline_icon_text_phrase("success", "Success Tokens")}{" added to the "}{inline_icon_text_phrase("alien", "Alien Base")}{" during the "}{inline_icon_text_phrase("interceptor", "UFO Defence Task.")}{" Once the third "}{inline_icon_text_phrase("success", "Success Token")}{" has been added, the "}{inline_icon_text_phrase("alien", "Alien Base")}{" is destroyed and you win the game once panic has been increased."}
+                </p>
                 <p>
-                    {"For each continent with any remaining UFOs, increase the "}{inline_icon_text_phrase("panic", "Panic Track")}{" one space."}
+                    {"If the "}{inline_icon_text_phrase("panic", "Panic Track")}{" also reaches the alien space this round, the victory is a pyrrhic one."}
                 </p>
             </>
         },
         ResolutionPhasePrompt::AskForBoardState => html! {},
-        ResolutionPhasePrompt::ResolveContinentBonuses => html! {
-            <>
-                <p>
-                    {"For each continent with no remaining UFOs, gain that continent's bonus."}
-                </p>
-                <div class="prompt-success-outcome-container">
-                    <div class="float-left">
-                        <img class="icon-header" src="assets/icons/america.png" />
-                        <img class="icon-header" src="assets/icons/america-board-position.png" />
-                    </div>
-                    <h2 class="continent-bonus-header">{"America"}</h2>
-                    <h4 class="continent-bonus-header">{"Air and Space:"}</h4>
-                    <p>
-                        {"Add one "}{inline_icon_text_phrase("interceptor", "Interceptor")}{" from the supply to your reserves."}
-                    </p>
-                    <p>
-                        {"Increase your number of "}{inline_icon_text_phrase("satellite", "Satellites")}{" by 1 (to a maximum of 3)."}
-                    </p>
-                </div>
-                <div class="prompt-success-outcome-container">
-                    <div class="float-left">
-                        <img class="icon-header" src="assets/icons/africa.png" />
-                        <img class="icon-header" src="assets/icons/africa-board-position.png" />
-                    </div>
-                    <h2 class="continent-bonus-header">{"Africa"}</h2>
-                    <h4 class="continent-bonus-header">{"All In:"}</h4>
-                    <p>
-                        {"Take §2 from the supply and add it to your funds."}
-                    </p>
-                </div>
-                <div class="prompt-success-outcome-container">
-                    <div class="float-left">
-                        <img class="icon-header" src="assets/icons/eurasia.png" />
-                        <img class="icon-header" src="assets/icons/eurasia-board-position.png" />
-                    </div>
-                    <h2 class="continent-bonus-header">{"Eurasia"}</h2>
-                    <h4 class="continent-bonus-header">{"Expert Knowledge:"}</h4>
-                    <p>
-                        {"Add one "}{inline_icon_text_phrase("success", "Success Token")}{" to the "}{inline_icon_text_phrase("tech", "Technology")}{" currently selected for research."}
-                    </p>
-                    <p>
-                        {"If there is no "}{inline_icon_text_phrase("tech", "Technology")}{" currently selected, draw the first token from the "}{inline_icon_text_phrase("tech", "Technology Token stack")}{" and select it, then add the "}{inline_icon_text_phrase("success", "Success Token.")}
-                    </p>
+        ResolutionPhasePrompt::ResolveContinentBonuses => {
+            let continents = board_state.earned_continent_bonuses();
+            if continents.is_empty() {
+                html! {
                     <p>
-                        {"This may immediately complete the "}{inline_icon_text_phrase("research", "Research,")}{" granting you access to use the selected "}{inline_icon_text_phrase("tech", "Technology.")}
+                        {"No continents were cleared of UFOs this round, so no continent bonuses are gained."}
                     </p>
-                </div>
-            </>
-        },
+                }
+            } else {
+                html! {
+                    <>
+                        <p>
+                            {"Gain the bonus for each continent with no remaining UFOs:"}
+                        </p>
+                        {continents.iter().map(continent_bonus_html).collect::<Html>()}
+                    </>
+                }
+            }
+        }
         ResolutionPhasePrompt::CleanUp => html! {
             <>
                 <p>
@@ -0,0 +1,571 @@
+use std::fmt::Display;
+
+use crate::Phase;
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use xcom_1_card::{random_continent, random_seed, round_rng, Game, TechDeck};
+
+// Everything the app stores is kept under this prefix so it doesn't clash with anything else on
+//   the origin
+const NAMESPACE: &str = "x1c/";
+const SLOTS_KEY: &str = "slots";
+const SAVE_KEY: &str = "save";
+
+// Before save slots existed, there was a single save and nothing was namespaced
+const UNNAMESPACED_SAVE_KEY: &str = "Save";
+const LEGACY_GAMESTATE_KEY: &str = "GameState";
+const LEGACY_PHASE_KEY: &str = "Phase";
+const UNNAMESPACED_FINISHED_GAMES_KEY: &str = "FinishedGames";
+const UNNAMESPACED_PHASE_KEY_PREFIXES: [&str; 2] = ["TimedPhase_", "ResolutionPhase_"];
+
+pub type SlotId = u32;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveSlot {
+    pub id: SlotId,
+    pub name: String,
+    pub last_played_ms: u64,
+}
+
+impl SaveSlot {
+    // Creates a slot that will be added to the list of slots once the game is first saved
+    pub fn new() -> Self {
+        let id = list_slots()
+            .iter()
+            .map(|slot| slot.id + 1)
+            .max()
+            .unwrap_or(1);
+        Self {
+            id,
+            name: format!("Campaign {}", id),
+            last_played_ms: 0,
+        }
+    }
+
+    // Prefix for all keys belonging to this slot, including those used mid-phase
+    pub fn storage_prefix(&self) -> String {
+        slot_prefix(self.id)
+    }
+}
+
+pub fn namespaced_key(key: &str) -> String {
+    format!("{}{}", NAMESPACE, key)
+}
+
+fn slot_prefix(id: SlotId) -> String {
+    namespaced_key(&format!("slot/{}/", id))
+}
+
+fn keys_with_prefix(prefix: &str) -> Vec<String> {
+    let storage = LocalStorage::raw();
+    (0..storage.length().unwrap_or(0))
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter(|key| key.starts_with(prefix))
+        .collect()
+}
+
+// Most recently played first
+pub fn list_slots() -> Vec<SaveSlot> {
+    let mut slots: Vec<SaveSlot> = LocalStorage::get(namespaced_key(SLOTS_KEY)).unwrap_or_default();
+    slots.sort_by_key(|slot| std::cmp::Reverse(slot.last_played_ms));
+    slots
+}
+
+fn set_slots(slots: Vec<SaveSlot>) -> Result<(), StorageError> {
+    LocalStorage::set(namespaced_key(SLOTS_KEY), slots)
+}
+
+// Finished games are kept for replays when their slot is deleted
+pub fn delete_slot(id: SlotId) {
+    if let Ok((game, _)) = load(id) {
+        if game.result().is_some() && crate::replay::save_finished_game(game.log()).is_err() {
+            log::error!("Error saving finished game");
+        }
+    }
+    let slots = list_slots()
+        .into_iter()
+        .filter(|slot| slot.id != id)
+        .collect();
+    if set_slots(slots).is_err() {
+        log::error!("Error saving slots");
+    }
+    for key in keys_with_prefix(&slot_prefix(id)) {
+        LocalStorage::delete(key);
+    }
+}
+
+// Keys a slot uses to resume mid-phase, i.e. everything besides the save itself
+fn phase_keys(slot: &SaveSlot) -> Vec<String> {
+    let save_key = format!("{}{}", slot.storage_prefix(), SAVE_KEY);
+    keys_with_prefix(&slot.storage_prefix())
+        .into_iter()
+        .filter(|key| *key != save_key)
+        .collect()
+}
+
+// The raw values of the slot's mid-phase keys, so they can be put back when undoing
+pub fn read_phase_storage(slot: &SaveSlot) -> Vec<(String, String)> {
+    let storage = LocalStorage::raw();
+    phase_keys(slot)
+        .into_iter()
+        .filter_map(|key| {
+            storage
+                .get_item(&key)
+                .ok()
+                .flatten()
+                .map(|value| (key, value))
+        })
+        .collect()
+}
+
+// Replaces the slot's mid-phase keys with ones read earlier
+pub fn restore_phase_storage(slot: &SaveSlot, phase_storage: &[(String, String)]) {
+    for key in phase_keys(slot) {
+        LocalStorage::delete(key);
+    }
+    let storage = LocalStorage::raw();
+    for (key, value) in phase_storage.iter() {
+        if storage.set_item(key, value).is_err() {
+            log::error!("Error restoring {}", key);
+        }
+    }
+}
+
+// Bump this and add a migration whenever the saved game or phase changes shape
+//   0: Legacy keys holding the original flat game state
+//   1: Legacy keys holding a seeded game
+//   2: Envelope holding a game with a game log
+//   3: Game state records where the alien base is
+//   4: Game state holds the technology stack
+const CURRENT_SAVE_VERSION: u32 = 4;
+
+// Each migration takes the data from the version at its index to the next version
+const MIGRATIONS: [fn(Value) -> Result<Value, String>; CURRENT_SAVE_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SaveEnvelope {
+    version: u32,
+    data: Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SaveData {
+    game: Game,
+    phase: Phase,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    NoSavedGame,
+    Storage(StorageError),
+    NewerVersion(u32),
+    MigrationFailed { from_version: u32, reason: String },
+    Invalid(serde_json::Error),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSavedGame => write!(f, "There is no saved game."),
+            Self::Storage(e) => write!(f, "The saved game could not be read ({}).", e),
+            Self::NewerVersion(version) => write!(
+                f,
+                "The saved game is from a newer version of the app (save version {}).",
+                version
+            ),
+            Self::MigrationFailed {
+                from_version,
+                reason,
+            } => write!(
+                f,
+                "The saved game could not be upgraded from save version {} ({}).",
+                from_version, reason
+            ),
+            Self::Invalid(e) => write!(f, "The saved game is corrupted ({}).", e),
+        }
+    }
+}
+
+fn read_key(key: &str) -> Result<Option<Value>, LoadError> {
+    match LocalStorage::get(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(StorageError::KeyNotFound(_)) => Ok(None),
+        Err(e) => Err(LoadError::Storage(e)),
+    }
+}
+
+fn read_envelope(key: &str) -> Result<SaveEnvelope, LoadError> {
+    match read_key(key)? {
+        Some(envelope) => serde_json::from_value(envelope).map_err(LoadError::Invalid),
+        None => Err(LoadError::NoSavedGame),
+    }
+}
+
+// The single save from before save slots, which may predate the save envelope too
+fn read_unnamespaced_envelope() -> Result<SaveEnvelope, LoadError> {
+    if let Some(envelope) = read_key(UNNAMESPACED_SAVE_KEY)? {
+        return serde_json::from_value(envelope).map_err(LoadError::Invalid);
+    }
+    match (read_key(LEGACY_GAMESTATE_KEY)?, read_key(LEGACY_PHASE_KEY)?) {
+        (Some(game), Some(phase)) => Ok(SaveEnvelope {
+            version: if game.get("state").is_some() { 1 } else { 0 },
+            data: json!({ "game": game, "phase": phase }),
+        }),
+        _ => Err(LoadError::NoSavedGame),
+    }
+}
+
+pub fn save(slot: &SaveSlot, game: &Game, phase: &Phase) -> Result<(), StorageError> {
+    let data = serde_json::to_value(SaveData {
+        game: game.clone(),
+        phase: phase.clone(),
+    })?;
+    LocalStorage::set(
+        format!("{}{}", slot.storage_prefix(), SAVE_KEY),
+        SaveEnvelope {
+            version: CURRENT_SAVE_VERSION,
+            data,
+        },
+    )?;
+    let mut slots: Vec<SaveSlot> = list_slots()
+        .into_iter()
+        .filter(|other| other.id != slot.id)
+        .collect();
+    slots.push(SaveSlot {
+        last_played_ms: js_sys::Date::now() as u64,
+        ..slot.clone()
+    });
+    set_slots(slots)
+}
+
+pub fn load(id: SlotId) -> Result<(Game, Phase), LoadError> {
+    let SaveEnvelope { version, data } =
+        read_envelope(&format!("{}{}", slot_prefix(id), SAVE_KEY))?;
+    migrate(version, data)
+}
+
+// Brings saved data from any earlier version up to date
+fn migrate(version: u32, mut data: Value) -> Result<(Game, Phase), LoadError> {
+    if version > CURRENT_SAVE_VERSION {
+        return Err(LoadError::NewerVersion(version));
+    }
+    for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        data = migration(data).map_err(|reason| LoadError::MigrationFailed {
+            from_version: from_version as u32,
+            reason,
+        })?;
+    }
+    let SaveData { game, phase } = serde_json::from_value(data).map_err(LoadError::Invalid)?;
+    Ok((game, phase))
+}
+
+// Moves anything stored before namespacing into the namespace, with the old save in its own slot
+pub fn migrate_unnamespaced_storage() {
+    let storage = LocalStorage::raw();
+    let move_key = |from: &str, to: &str| {
+        if let Ok(Some(value)) = storage.get_item(from) {
+            if storage.set_item(to, &value).is_ok() {
+                LocalStorage::delete(from);
+            }
+        }
+    };
+    move_key(
+        UNNAMESPACED_FINISHED_GAMES_KEY,
+        &crate::replay::finished_games_key(),
+    );
+    if let Ok(envelope) = read_unnamespaced_envelope() {
+        let slot = SaveSlot {
+            name: "Saved Game".to_owned(),
+            last_played_ms: js_sys::Date::now() as u64,
+            ..SaveSlot::new()
+        };
+        let prefix = slot.storage_prefix();
+        // Older save versions are migrated as usual when the slot is loaded
+        if LocalStorage::set(format!("{}{}", prefix, SAVE_KEY), envelope).is_err() {
+            log::error!("Error moving saved game into a slot");
+            return;
+        }
+        let mut slots = list_slots();
+        slots.push(slot);
+        if set_slots(slots).is_err() {
+            log::error!("Error saving slots");
+        }
+        for key in UNNAMESPACED_PHASE_KEY_PREFIXES
+            .iter()
+            .flat_map(|phase_prefix| keys_with_prefix(phase_prefix))
+        {
+            move_key(&key, &format!("{}{}", prefix, key));
+        }
+        LocalStorage::delete(UNNAMESPACED_SAVE_KEY);
+        LocalStorage::delete(LEGACY_GAMESTATE_KEY);
+        LocalStorage::delete(LEGACY_PHASE_KEY);
+    }
+}
+
+// The original game state wasn't seeded, so the rest of the game gets a new seed on Normal
+fn migrate_v0_to_v1(mut data: Value) -> Result<Value, String> {
+    let old_state = data
+        .get_mut("game")
+        .map(Value::take)
+        .ok_or("missing game state")?;
+    let field = |name: &str| {
+        old_state
+            .get(name)
+            .cloned()
+            .ok_or(format!("missing {}", name))
+    };
+    data["game"] = json!({
+        "state": {
+            "seed": random_seed(),
+            "difficulty": "Normal",
+            "round": field("round")?,
+            "alien_base_discovered": field("alien_base_discovered")?,
+            "panic_level": field("panic_level")?,
+            "ufos_left": field("ufos_left")?,
+        },
+        "result": null,
+    });
+    Ok(data)
+}
+
+// Games saved before the game log existed start with an empty log
+fn migrate_v1_to_v2(mut data: Value) -> Result<Value, String> {
+    let game = data.get_mut("game").ok_or("missing game")?;
+    if game.get("log").is_none() {
+        let state = game.get("state").ok_or("missing game state")?;
+        game["log"] = json!({
+            "seed": state.get("seed").ok_or("missing seed")?,
+            "difficulty": state.get("difficulty").ok_or("missing difficulty")?,
+            "result": game.get("result").cloned().unwrap_or(Value::Null),
+            "rounds": [],
+        });
+    }
+    Ok(data)
+}
+
+// The alien base's continent comes from the prompt that discovered it, in either the log or the
+//   round being played. Games that lost their log to an earlier migration can't say where it was
+//   found, so the base is placed on a continent picked using the game's seed, the same one every
+//   time the save is loaded
+fn migrate_v2_to_v3(mut data: Value) -> Result<Value, String> {
+    let game = data.get_mut("game").ok_or("missing game")?;
+    let state = game.get("state").ok_or("missing game state")?;
+    let seed = state
+        .get("seed")
+        .and_then(Value::as_u64)
+        .ok_or("missing seed")?;
+    let alien_base_discovered = state
+        .get("alien_base_discovered")
+        .and_then(Value::as_bool)
+        .ok_or("missing alien base discovered")?;
+    let alien_base = if alien_base_discovered {
+        let logged_prompts = game
+            .pointer("/log/rounds")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|round| round.get("prompts").and_then(Value::as_array))
+            .flatten();
+        let current_prompts = game
+            .pointer("/round_in_progress/prompts")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten();
+        let continent = match logged_prompts
+            .chain(current_prompts)
+            .find_map(|prompt| prompt.get("AlienBaseDiscovered").cloned())
+        {
+            Some(continent) => continent,
+            None => serde_json::to_value(random_continent(&mut round_rng(seed, 0)))
+                .map_err(|e| e.to_string())?,
+        };
+        json!({ "continent": continent, "damage": 0 })
+    } else {
+        Value::Null
+    };
+    let state = game
+        .get_mut("state")
+        .and_then(Value::as_object_mut)
+        .ok_or("missing game state")?;
+    state.remove("alien_base_discovered");
+    state.insert("alien_base".to_owned(), alien_base);
+    Ok(data)
+}
+
+// Games from before technology was tracked get the stack a new game with their seed would have
+//   been dealt, so it's the same every time the save is loaded
+fn migrate_v3_to_v4(mut data: Value) -> Result<Value, String> {
+    let state = data
+        .pointer_mut("/game/state")
+        .and_then(Value::as_object_mut)
+        .ok_or("missing game state")?;
+    if !state.contains_key("tech_deck") {
+        let seed = state
+            .get("seed")
+            .and_then(Value::as_u64)
+            .ok_or("missing seed")?;
+        let tech_deck = serde_json::to_value(TechDeck::shuffled(&mut round_rng(seed, 0)))
+            .map_err(|e| e.to_string())?;
+        state.insert("tech_deck".to_owned(), tech_deck);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use xcom_1_card::{Continent, Difficulty, PanicTrack, ORANGE_PANIC_SPACE};
+
+    fn v0_save(alien_base_discovered: bool) -> Value {
+        json!({
+            "game": {
+                "round": 4,
+                "alien_base_discovered": alien_base_discovered,
+                "panic_level": "Orange",
+                "ufos_left": 3,
+            },
+            "phase": "PrepareForTimedPhase",
+        })
+    }
+
+    fn v2_save(rounds: Value, round_in_progress: Value) -> Value {
+        json!({
+            "game": {
+                "state": {
+                    "seed": 1234,
+                    "difficulty": "Hard",
+                    "round": 6,
+                    "alien_base_discovered": true,
+                    "panic": 5,
+                    "ufos_left": 2,
+                },
+                "result": null,
+                "log": {
+                    "seed": 1234,
+                    "difficulty": "Hard",
+                    "result": null,
+                    "rounds": rounds,
+                },
+                "round_in_progress": round_in_progress,
+            },
+            "phase": "PrepareForResolutionPhase",
+        })
+    }
+
+    fn logged_round(prompts: Value) -> Value {
+        json!({
+            "round": 5,
+            "prompts": prompts,
+            "board_state": {
+                "panic": 5,
+                "ufos_left": [0, 1, 1],
+                "alien_base_destroyed": false,
+            },
+            "alien_base_discovered": true,
+            "started_at_ms": 0,
+            "completed_at_ms": 0,
+        })
+    }
+
+    #[test]
+    fn migrate_v0_test() {
+        let (game, phase) = migrate(0, v0_save(false)).unwrap();
+        let state = game.state();
+        assert_eq!(Difficulty::Normal, state.difficulty);
+        assert_eq!(4, state.round);
+        assert_eq!(PanicTrack::new(ORANGE_PANIC_SPACE), state.panic);
+        assert_eq!(3, state.ufos_left);
+        assert_eq!(None, state.alien_base);
+        assert!(game.log().rounds().is_empty());
+        assert!(matches!(phase, Phase::PrepareForTimedPhase));
+    }
+
+    #[test]
+    fn migrate_v1_test() {
+        let mut data = v0_save(false);
+        data = migrate_v0_to_v1(data).unwrap();
+        data["game"]["state"]["seed"] = json!(42);
+        data["game"]["state"]["difficulty"] = json!("Easy");
+        let (game, _) = migrate(1, data).unwrap();
+        assert_eq!(42, game.state().seed);
+        assert_eq!(42, game.log().seed);
+        assert_eq!(Difficulty::Easy, game.log().difficulty);
+    }
+
+    #[test]
+    fn migrate_v2_finds_logged_alien_base_test() {
+        let data = v2_save(
+            json!([logged_round(json!([
+                { "TakeIncome": 3 },
+                { "AlienBaseDiscovered": "Africa" },
+            ]))]),
+            Value::Null,
+        );
+        let (game, _) = migrate(2, data).unwrap();
+        let alien_base = game.state().alien_base.as_ref().unwrap();
+        assert_eq!(&Continent::Africa, alien_base.continent());
+        assert_eq!(0, alien_base.damage());
+        assert_eq!(1, game.log().rounds().len());
+    }
+
+    #[test]
+    fn migrate_v2_finds_alien_base_in_round_in_progress_test() {
+        let data = v2_save(
+            json!([]),
+            json!({
+                "prompts": [{ "AlienBaseDiscovered": "Eurasia" }],
+                "started_at_ms": 0,
+            }),
+        );
+        let (game, _) = migrate(2, data).unwrap();
+        assert_eq!(
+            Some(&Continent::Eurasia),
+            game.state()
+                .alien_base
+                .as_ref()
+                .map(|alien_base| alien_base.continent())
+        );
+    }
+
+    #[test]
+    fn migrate_v2_places_unfound_alien_base_from_seed_test() {
+        let (first, _) = migrate(2, v2_save(json!([]), Value::Null)).unwrap();
+        let (second, _) = migrate(2, v2_save(json!([]), Value::Null)).unwrap();
+        assert!(first.state().alien_base.is_some());
+        assert_eq!(first.state().alien_base, second.state().alien_base);
+    }
+
+    #[test]
+    fn migrate_v3_shuffles_tech_deck_from_seed_test() {
+        let (first, _) = migrate(2, v2_save(json!([]), Value::Null)).unwrap();
+        let (second, _) = migrate(2, v2_save(json!([]), Value::Null)).unwrap();
+        assert_eq!(
+            TechDeck::shuffled(&mut round_rng(1234, 0)),
+            first.state().tech_deck
+        );
+        assert_eq!(first.state().tech_deck, second.state().tech_deck);
+    }
+
+    #[test]
+    fn migrate_v3_keeps_tracked_tech_deck_test() {
+        let mut data = migrate_v2_to_v3(v2_save(json!([]), Value::Null)).unwrap();
+        let tech_deck = TechDeck::shuffled(&mut round_rng(42, 0));
+        data["game"]["state"]["tech_deck"] = serde_json::to_value(&tech_deck).unwrap();
+        let (game, _) = migrate(3, data).unwrap();
+        assert_eq!(tech_deck, game.state().tech_deck);
+    }
+
+    #[test]
+    fn newer_version_test() {
+        assert!(matches!(
+            migrate(CURRENT_SAVE_VERSION + 1, json!({})),
+            Err(LoadError::NewerVersion(_))
+        ));
+    }
+}
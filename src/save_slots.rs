@@ -0,0 +1,121 @@
+use crate::save::{self, SaveSlot, SlotId};
+use yew::prelude::*;
+
+pub enum Msg {
+    Select(SlotId),
+}
+
+// Lists the save slots so one can be loaded or deleted
+pub struct SaveSlots {
+    slots: Vec<SaveSlot>,
+    confirming_delete: Option<SlotId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    pub deleting: bool,
+    pub on_load: Callback<SlotId>,
+    pub on_main_menu: Callback<()>,
+}
+
+fn format_last_played(last_played_ms: u64) -> String {
+    let date = js_sys::Date::new_0();
+    date.set_time(last_played_ms as f64);
+    String::from(date.to_locale_string("default", &js_sys::Object::new()))
+}
+
+fn slot_details(slot: &SaveSlot) -> String {
+    match save::load(slot.id) {
+        Ok((game, phase)) => format!(
+            "Round {} - {} Panic - {} - Last played {}",
+            game.state().round,
+            game.state().panic.level(),
+            phase.description(),
+            format_last_played(slot.last_played_ms)
+        ),
+        Err(e) => e.to_string(),
+    }
+}
+
+impl Component for SaveSlots {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            slots: save::list_slots(),
+            confirming_delete: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Select(id) => {
+                if !ctx.props().deleting {
+                    ctx.props().on_load.emit(id);
+                    false
+                } else if self.confirming_delete == Some(id) {
+                    save::delete_slot(id);
+                    self.slots = save::list_slots();
+                    self.confirming_delete = None;
+                    true
+                } else {
+                    self.confirming_delete = Some(id);
+                    true
+                }
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        self.confirming_delete = None;
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let button_text = |id: SlotId| match (ctx.props().deleting, self.confirming_delete) {
+            (false, _) => "Load",
+            (true, Some(confirming)) if confirming == id => "Confirm",
+            (true, _) => "Delete",
+        };
+        html! {
+            <>
+                <h1 class="prompt-title">{ if ctx.props().deleting { "Delete Game" } else { "Load Game" } }</h1>
+                <div class="prompt-center-area">
+                    <div class="side-buttons">
+                    </div>
+                    <div class="prompt-details">
+                        <div class="save-slot-list">
+                            {
+                                if self.slots.is_empty() {
+                                    html! {
+                                        <div class="prompt-description">{ "There are no saved games." }</div>
+                                    }
+                                } else {
+                                    self.slots
+                                        .iter()
+                                        .map(|slot| {
+                                            let id = slot.id;
+                                            html! {
+                                                <div class="save-slot">
+                                                    <div class="save-slot-name">{ slot.name.clone() }</div>
+                                                    <div class="save-slot-details">{ slot_details(slot) }</div>
+                                                    <button class="save-slot-button" onclick={ctx.link().callback(move |_| Msg::Select(id))}>
+                                                        { button_text(id) }
+                                                    </button>
+                                                </div>
+                                            }
+                                        })
+                                        .collect::<Html>()
+                                }
+                            }
+                        </div>
+                    </div>
+                </div>
+                <div class="bottom-panel">
+                    <button class="button-back" onclick={ctx.props().on_main_menu.reform(|_| ())}>{ "Main Menu" }</button>
+                </div>
+            </>
+        }
+    }
+}
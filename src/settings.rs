@@ -0,0 +1,260 @@
+use crate::save;
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use xcom_1_card::Difficulty;
+use yew::prelude::*;
+
+const COUNTDOWN_STEP_MS: f64 = 1_000.0;
+const MAX_COUNTDOWN_MS: f64 = 10_000.0;
+const STARTING_TIME_STEP_MS: f64 = 5_000.0;
+const TIME_PER_PROMPT_STEP_MS: f64 = 1_000.0;
+const RELOAD_GRACE_STEP_MS: f64 = 500.0;
+const MAX_RELOAD_GRACE_MS: f64 = 5_000.0;
+
+// Kept outside of the save slots so they carry over between games
+pub fn settings_key() -> String {
+    save::namespaced_key("settings")
+}
+
+// Preferences for the timers. Missing fields are filled in from the defaults, so settings saved by
+//   older versions still load
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Countdown before the timed phase starts
+    pub countdown_time_ms: f64,
+    // Timed phase times, replacing the ones for the difficulty when set
+    pub starting_time_ms: Option<f64>,
+    pub time_per_prompt_ms: Option<f64>,
+    // Extra time given when the timed phase is reloaded, to re-read the prompt
+    pub reload_grace_ms: f64,
+    // Whether the timers stop while the app isn't in focus
+    pub pause_when_unfocused: bool,
+    // Whether the timed phase shows the next prompt's category face down
+    pub preview_next_prompt: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            countdown_time_ms: 3_000.0,
+            starting_time_ms: None,
+            time_per_prompt_ms: None,
+            reload_grace_ms: 1_000.0,
+            pause_when_unfocused: true,
+            preview_next_prompt: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        LocalStorage::get(settings_key()).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        LocalStorage::set(settings_key(), self)
+    }
+
+    pub fn timed_phase_starting_time_ms(&self, difficulty: Difficulty) -> f64 {
+        self.starting_time_ms
+            .unwrap_or_else(|| difficulty.timed_phase_starting_time_ms())
+    }
+
+    pub fn timed_phase_time_per_prompt_ms(&self, difficulty: Difficulty) -> f64 {
+        self.time_per_prompt_ms
+            .unwrap_or_else(|| difficulty.timed_phase_time_per_prompt_ms())
+    }
+}
+
+fn seconds_text(time_ms: f64) -> String {
+    format!("{}s", time_ms / 1000.0)
+}
+
+#[derive(Clone, Copy)]
+pub enum Msg {
+    IncreaseCountdown,
+    DecreaseCountdown,
+    IncreaseStartingTime,
+    DecreaseStartingTime,
+    IncreaseTimePerPrompt,
+    DecreaseTimePerPrompt,
+    UseDifficultyTimes,
+    IncreaseReloadGrace,
+    DecreaseReloadGrace,
+    UpdatePauseWhenUnfocused(bool),
+    UpdatePreviewNextPrompt(bool),
+    ResetToDefaults,
+}
+
+// Lets the player change the settings, saving them as they go
+pub struct SettingsMenu {
+    settings: Settings,
+}
+
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct Props {
+    pub on_main_menu: Callback<()>,
+}
+
+impl Component for SettingsMenu {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            settings: Settings::load(),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let settings = &mut self.settings;
+        // Custom times start from the default difficulty's
+        let starting_time_ms = settings.timed_phase_starting_time_ms(Difficulty::default());
+        let time_per_prompt_ms = settings.timed_phase_time_per_prompt_ms(Difficulty::default());
+        match msg {
+            Msg::IncreaseCountdown => {
+                settings.countdown_time_ms = f64::min(
+                    settings.countdown_time_ms + COUNTDOWN_STEP_MS,
+                    MAX_COUNTDOWN_MS,
+                );
+            }
+            Msg::DecreaseCountdown => {
+                settings.countdown_time_ms =
+                    f64::max(settings.countdown_time_ms - COUNTDOWN_STEP_MS, 0.0);
+            }
+            Msg::IncreaseStartingTime => {
+                settings.starting_time_ms = Some(starting_time_ms + STARTING_TIME_STEP_MS);
+            }
+            Msg::DecreaseStartingTime => {
+                settings.starting_time_ms = Some(f64::max(
+                    starting_time_ms - STARTING_TIME_STEP_MS,
+                    STARTING_TIME_STEP_MS,
+                ));
+            }
+            Msg::IncreaseTimePerPrompt => {
+                settings.time_per_prompt_ms = Some(time_per_prompt_ms + TIME_PER_PROMPT_STEP_MS);
+            }
+            Msg::DecreaseTimePerPrompt => {
+                settings.time_per_prompt_ms =
+                    Some(f64::max(time_per_prompt_ms - TIME_PER_PROMPT_STEP_MS, 0.0));
+            }
+            Msg::UseDifficultyTimes => {
+                settings.starting_time_ms = None;
+                settings.time_per_prompt_ms = None;
+            }
+            Msg::IncreaseReloadGrace => {
+                settings.reload_grace_ms = f64::min(
+                    settings.reload_grace_ms + RELOAD_GRACE_STEP_MS,
+                    MAX_RELOAD_GRACE_MS,
+                );
+            }
+            Msg::DecreaseReloadGrace => {
+                settings.reload_grace_ms =
+                    f64::max(settings.reload_grace_ms - RELOAD_GRACE_STEP_MS, 0.0);
+            }
+            Msg::UpdatePauseWhenUnfocused(pause_when_unfocused) => {
+                settings.pause_when_unfocused = pause_when_unfocused;
+            }
+            Msg::UpdatePreviewNextPrompt(preview_next_prompt) => {
+                settings.preview_next_prompt = preview_next_prompt;
+            }
+            Msg::ResetToDefaults => {
+                *settings = Settings::default();
+            }
+        }
+        if self.settings.save().is_err() {
+            log::error!("Error saving settings");
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let settings = &self.settings;
+        let stepper_html = |text: String, decrease: Msg, increase: Msg| {
+            html! {
+                <div class="ufo-input-container">
+                    <img class="ufo-input-continent-icon" src="assets/icons/time.png"/>
+                    <button class="ufo-input-button" onclick={ctx.link().callback(move |_| decrease)}>{"-"}</button>
+                    <span class="ufo-input-text">{ text }</span>
+                    <button class="ufo-input-button" onclick={ctx.link().callback(move |_| increase)}>{"+"}</button>
+                </div>
+            }
+        };
+        html! {
+            <>
+                <h1 class="prompt-title">{ "Settings" }</h1>
+                <div class="prompt-center-area">
+                    <div class="side-buttons">
+                    </div>
+                    <div class="prompt-details">
+                        <div class="board-input-title">{ "Countdown before the Timed Phase:" }</div>
+                        {stepper_html(seconds_text(settings.countdown_time_ms), Msg::DecreaseCountdown, Msg::IncreaseCountdown)}
+                        <div class="board-input-title">{ "Timed Phase starting time:" }</div>
+                        {
+                            stepper_html(
+                                settings.starting_time_ms.map_or_else(|| "Difficulty".to_owned(), seconds_text),
+                                Msg::DecreaseStartingTime,
+                                Msg::IncreaseStartingTime,
+                            )
+                        }
+                        <div class="board-input-title">{ "Extra time per prompt:" }</div>
+                        {
+                            stepper_html(
+                                settings.time_per_prompt_ms.map_or_else(|| "Difficulty".to_owned(), seconds_text),
+                                Msg::DecreaseTimePerPrompt,
+                                Msg::IncreaseTimePerPrompt,
+                            )
+                        }
+                        <button
+                            class="save-slot-button"
+                            onclick={ctx.link().callback(|_| Msg::UseDifficultyTimes)}
+                            disabled={settings.starting_time_ms.is_none() && settings.time_per_prompt_ms.is_none()}
+                        >
+                            { "Use Difficulty Times" }
+                        </button>
+                        <div class="prompt-description">
+                            { "By default the Timed Phase gives as much time as the difficulty allows. Custom times apply to every game, whatever its difficulty." }
+                        </div>
+                        <div class="board-input-title">{ "Extra time after reloading:" }</div>
+                        {stepper_html(seconds_text(settings.reload_grace_ms), Msg::DecreaseReloadGrace, Msg::IncreaseReloadGrace)}
+                        <div class="alien-base-destroyed-input-container">
+                            <label for="pause_when_unfocused_input">{ "Pause timers while in the background?" }</label>
+                            <input
+                                class="alien-base-destroyed-input-checkbox"
+                                type="checkbox"
+                                name="pause_when_unfocused_input"
+                                checked={settings.pause_when_unfocused}
+                                onchange={ctx.link().batch_callback(|e: Event| {
+                                    if let Some(input_element) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        return vec![Msg::UpdatePauseWhenUnfocused(input_element.checked())];
+                                    }
+                                    vec![]
+                                })}
+                            />
+                        </div>
+                        <div class="alien-base-destroyed-input-container">
+                            <label for="preview_next_prompt_input">{ "Preview the next prompt face down?" }</label>
+                            <input
+                                class="alien-base-destroyed-input-checkbox"
+                                type="checkbox"
+                                name="preview_next_prompt_input"
+                                checked={settings.preview_next_prompt}
+                                onchange={ctx.link().batch_callback(|e: Event| {
+                                    if let Some(input_element) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        return vec![Msg::UpdatePreviewNextPrompt(input_element.checked())];
+                                    }
+                                    vec![]
+                                })}
+                            />
+                        </div>
+                    </div>
+                </div>
+                <div class="bottom-panel">
+                    <button class="button-back" onclick={ctx.props().on_main_menu.reform(|_| ())}>{ "Main Menu" }</button>
+                    <button class="button-back" onclick={ctx.link().callback(|_| Msg::ResetToDefaults)}>{ "Reset to Defaults" }</button>
+                </div>
+            </>
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::Display;
 
 use crate::common::inline_icon_text_phrase;
 use boolinator::Boolinator;
+use xcom_1_card::{ResolutionPhasePrompt, Tech, TechDeck, TimedPhasePrompt, ALL_TECHS};
 use yew::prelude::*;
 
 enum PhaseUsage {
@@ -43,40 +44,103 @@ impl TechInfo {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Tech {
-    UFOTracking,
-    UFONavigation,
-    HyperwaveCommunication,
-    DefenceMatrix,
-    EMPCannon,
-    Firestorm,
-    AlienIntel,
-    CarapaceArmour,
-    EleriumGenerator,
+pub fn tech_icon(tech: &Tech) -> String {
+    tech.get_info().icon
 }
 
-impl Tech {
-    fn get_all() -> Vec<Tech> {
-        vec![
-            Self::UFOTracking,
-            Self::UFONavigation,
-            Self::HyperwaveCommunication,
-            Self::DefenceMatrix,
-            Self::EMPCannon,
-            Self::Firestorm,
-            Self::AlienIntel,
-            Self::CarapaceArmour,
-            Self::EleriumGenerator,
-        ]
+// Where the player is in the round, for reminding them which techs they can use.
+//   A timed prompt of None is the final chance to use techs before the timer ends
+pub enum TechTiming<'a> {
+    Timed(Option<&'a TimedPhasePrompt>),
+    Resolution(&'a ResolutionPhasePrompt),
+}
+
+// Whether the tech's power is relevant at this point in the round
+fn tech_usable(tech: &Tech, timing: &TechTiming) -> bool {
+    match (tech.get_info().phase, timing) {
+        (PhaseUsage::Timed, TechTiming::Timed(None)) => true,
+        (PhaseUsage::Timed, TechTiming::Timed(Some(prompt))) => matches!(
+            (tech, prompt),
+            (
+                Tech::HyperwaveCommunication,
+                TimedPhasePrompt::RollUFOLocation(_)
+            ) | (
+                Tech::UFONavigation,
+                TimedPhasePrompt::AddUFOsToLocation(_, _)
+                    | TimedPhasePrompt::SwapUFOLocations(_, _)
+            ) | (Tech::UFOTracking, TimedPhasePrompt::AssignInterceptors(_))
+                | (
+                    Tech::EleriumGenerator,
+                    TimedPhasePrompt::TakeIncome(_) | TimedPhasePrompt::SetResearchBudget
+                )
+        ),
+        (PhaseUsage::Resolution, TechTiming::Resolution(prompt)) => matches!(
+            (tech, prompt),
+            (
+                Tech::Firestorm | Tech::DefenceMatrix,
+                ResolutionPhasePrompt::ResolveUFODefence
+            ) | (
+                Tech::EMPCannon | Tech::CarapaceArmour | Tech::AlienIntel,
+                ResolutionPhasePrompt::ResolveResearch | ResolutionPhasePrompt::ResolveUFODefence
+            )
+        ),
+        _ => false,
+    }
+}
+
+// Reminds the player of the owned techs they may use now, letting them mark each as used
+//   for the round
+pub fn tech_hints_html(tech_deck: &TechDeck, timing: TechTiming, on_use: Callback<Tech>) -> Html {
+    let usable_techs = tech_deck
+        .owned()
+        .iter()
+        .filter(|tech| tech_usable(tech, &timing))
+        .collect::<Vec<_>>();
+    if usable_techs.is_empty() {
+        return html! {};
     }
+    html! {
+        <div class="tech-hints">
+            {
+                usable_techs
+                    .into_iter()
+                    .map(|tech| {
+                        let exhausted = tech_deck.is_exhausted(tech);
+                        let used_tech = tech.clone();
+                        html! {
+                            <div class={classes!("tech-hint", exhausted.as_some("exhausted"))}>
+                                <img class="tech-hint-icon" src={tech_icon(tech)}/>
+                                <span>
+                                    {
+                                        if exhausted {
+                                            format!("{} has been used this round", tech)
+                                        } else {
+                                            format!("You may use {} now", tech)
+                                        }
+                                    }
+                                </span>
+                                <button class="tech-hint-button" onclick={on_use.reform(move |_| used_tech.clone())} disabled={exhausted}>{ "Mark used" }</button>
+                            </div>
+                        }
+                    })
+                    .collect::<Html>()
+            }
+        </div>
+    }
+}
+
+// What the reference shows about each of the library's techs
+trait TechReferenceInfo {
+    fn get_info(&self) -> TechInfo;
+}
 
+impl TechReferenceInfo for Tech {
     fn get_info(&self) -> TechInfo {
         match self {
             Self::HyperwaveCommunication => TechInfo {
-                name: "Hyperwave Communication".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/hyperwave-communication.png".to_owned(),
-                cost: 3,
+                cost: self.cost(),
                 phase: PhaseUsage::Timed,
                 description: html! {
                     <>
@@ -85,9 +149,9 @@ impl Tech {
                 },
             },
             Self::UFONavigation => TechInfo {
-                name: "UFO Navigation".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/UFO-navigation.png".to_owned(),
-                cost: 2,
+                cost: self.cost(),
                 phase: PhaseUsage::Timed,
                 description: html! {
                     <>
@@ -96,9 +160,9 @@ impl Tech {
                 },
             },
             Self::UFOTracking => TechInfo {
-                name: "UFO Tracking".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/UFO-tracking.png".to_owned(),
-                cost: 1,
+                cost: self.cost(),
                 phase: PhaseUsage::Timed,
                 description: html! {
                     <>
@@ -107,9 +171,9 @@ impl Tech {
                 },
             },
             Self::Firestorm => TechInfo {
-                name: "Firestorm".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/firestorm.png".to_owned(),
-                cost: 3,
+                cost: self.cost(),
                 phase: PhaseUsage::Resolution,
                 description: html! {
                     <>
@@ -118,9 +182,9 @@ impl Tech {
                 },
             },
             Self::EMPCannon => TechInfo {
-                name: "EMP Cannon".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/EMP-cannon.png".to_owned(),
-                cost: 2,
+                cost: self.cost(),
                 phase: PhaseUsage::Resolution,
                 description: html! {
                     <>
@@ -129,9 +193,9 @@ impl Tech {
                 },
             },
             Self::DefenceMatrix => TechInfo {
-                name: "Defence Matrix".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/defence-matrix.png".to_owned(),
-                cost: 1,
+                cost: self.cost(),
                 phase: PhaseUsage::Resolution,
                 description: html! {
                     <>
@@ -140,9 +204,9 @@ impl Tech {
                 },
             },
             Self::EleriumGenerator => TechInfo {
-                name: "Elerium Generator".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/elerium-generator.png".to_owned(),
-                cost: 3,
+                cost: self.cost(),
                 phase: PhaseUsage::Timed,
                 description: html! {
                     <>
@@ -151,9 +215,9 @@ impl Tech {
                 },
             },
             Self::CarapaceArmour => TechInfo {
-                name: "Carapace Armour".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/carapace-armour.png".to_owned(),
-                cost: 2,
+                cost: self.cost(),
                 phase: PhaseUsage::Resolution,
                 description: html! {
                     <>
@@ -162,9 +226,9 @@ impl Tech {
                 },
             },
             Self::AlienIntel => TechInfo {
-                name: "Alien Intel".to_owned(),
+                name: self.name(),
                 icon: "assets/tech/alien-intel.png".to_owned(),
-                cost: 1,
+                cost: self.cost(),
                 phase: PhaseUsage::Resolution,
                 description: html! {
                     <>
@@ -209,7 +273,7 @@ impl Component for TechReference {
             <>
                 <div class="tech-ref-grid">
                     {
-                        Tech::get_all().into_iter().map(|tech| html!{
+                        ALL_TECHS.into_iter().map(|tech| html!{
                             <img
                                 class={classes!(
                                     "tech-icon",
@@ -1,20 +1,29 @@
-use crate::{common::Focus, tech_reference::TechReference};
 use crate::{
-    common::{inline_icon_text_phrase, side_buttons},
+    common::Focus,
+    tech_reference::{tech_hints_html, tech_icon, TechReference, TechTiming},
+};
+use crate::{
+    common::{forces_html, inline_icon_text_phrase, research_html, side_buttons},
     rules::rules_reference,
+    ufo_map::{die_input_html, ufo_map_html, UFO_TRACKER_KEY},
 };
 use boolinator::Boolinator;
 use gloo::{timers::callback::Interval, utils::document};
 use gloo_storage::{LocalStorage, Storage};
 use web_sys::Element;
-use xcom_1_card::TimedPhasePrompt;
+use xcom_1_card::{
+    roll_missing_ufo_dice, Continent, Forces, Tech, TechDeck, TimedPhasePrompt, TimerExpiryRule,
+    UfoTracker, TIMER_EXPIRY_PANIC,
+};
 use yew::prelude::*;
 
 const LATEST_PROMPT_INDEX_KEY: &str = "TimedPhase_LatestPromptIndex";
 const TIME_REMANING_KEY: &str = "TimedPhase_TimeRemaining";
-
-const STARTING_TIME: f64 = 16_000.0;
-const TIME_PER_PROMPT: f64 = 8_000.0;
+const UFO_ROLLS_KEY: &str = "TimedPhase_UFORolls";
+const FORCES_KEY: &str = "TimedPhase_Forces";
+const TECH_DECK_KEY: &str = "TimedPhase_TechDeck";
+const RESEARCH_CHOICE_KEY: &str = "TimedPhase_ResearchChoice";
+const TIMER_EXPIRED_KEY: &str = "TimedPhase_TimerExpired";
 
 pub enum Msg {
     NextPrompt,
@@ -22,6 +31,13 @@ pub enum Msg {
     Tick,
     ToggleTech,
     ToggleResearch,
+    EnterUFORoll(u32),
+    DeployInterceptor(Continent),
+    RecallInterceptor(Continent),
+    UseSatellite,
+    ChooseResearch(Option<Tech>),
+    UseTech(Tech),
+    JumpToPrompt(usize),
     OnCompleted,
 }
 
@@ -33,14 +49,130 @@ pub struct TimedPhase {
     tick_interval: Interval,
     focus: Focus,
     prompt_details_ref: NodeRef,
+    // Die results entered for each prompt, if any
+    ufo_rolls: Vec<Option<u32>>,
+    forces: Forces,
+    // Drawn at the start of the round so the choices stay the same after reloading
+    tech_deck: TechDeck,
+    // The drawn tech to research, or None to carry on with the current research
+    research_choice: Option<Tech>,
+    timer_expired: bool,
+}
+
+// What changed on the board during the timed phase
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedPhaseOutcome {
+    pub forces: Forces,
+    pub tech_deck: TechDeck,
+    pub timer_expired: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Properties)]
 pub struct TimedPhaseProps {
     pub prompts: Vec<TimedPhasePrompt>,
     pub round: u32,
-    pub on_completed: Callback<()>,
-    pub on_alien_base_discovered: Callback<()>,
+    pub starting_time_ms: f64,
+    pub time_per_prompt_ms: f64,
+    // Extra time given when the phase is reloaded
+    pub reload_grace_ms: f64,
+    pub pause_when_unfocused: bool,
+    // Forces at the start of the round, before any interceptors are deployed
+    pub forces: Forces,
+    pub tech_deck: TechDeck,
+    pub timer_expiry_rule: TimerExpiryRule,
+    // Whether to show the next prompt's category face down in the preview
+    pub preview_next_prompt: bool,
+    // Ironman games can't go back to earlier prompts
+    pub ironman: bool,
+    pub on_completed: Callback<TimedPhaseOutcome>,
+    pub storage_prefix: String,
+}
+
+impl TimedPhaseProps {
+    fn storage_key(&self, key: &str) -> String {
+        format!("{}{}", self.storage_prefix, key)
+    }
+}
+
+// Removes what the timed phase saved to resume after a reload. Left to the caller once the phase
+//   is completed, so it can be kept for undoing
+pub fn clear_storage(storage_prefix: &str) {
+    for key in [
+        LATEST_PROMPT_INDEX_KEY,
+        TIME_REMANING_KEY,
+        UFO_ROLLS_KEY,
+        FORCES_KEY,
+        TECH_DECK_KEY,
+        RESEARCH_CHOICE_KEY,
+        TIMER_EXPIRED_KEY,
+    ] {
+        LocalStorage::delete(format!("{}{}", storage_prefix, key));
+    }
+}
+
+impl TimedPhase {
+    fn save_forces(&self, props: &TimedPhaseProps) {
+        if LocalStorage::set(props.storage_key(FORCES_KEY), &self.forces).is_err() {
+            log::error!("Error saving forces");
+        }
+    }
+
+    // Applies the expiry rule the first time the timer runs out. Ending the phase skips to the
+    //   final prompt, rolling any UFO dice not yet entered. The decisions still to come are lost:
+    //   continents whose interceptors hadn't been assigned yet get none, and no new research is
+    //   chosen
+    fn expire_timer(&mut self, props: &TimedPhaseProps) {
+        self.timer_expired = true;
+        if LocalStorage::set(props.storage_key(TIMER_EXPIRED_KEY), true).is_err() {
+            log::error!("Error saving timer expired");
+        }
+        if let TimerExpiryRule::EndPhase = props.timer_expiry_rule {
+            let current = usize::min(self.latest_prompt_index, props.prompts.len());
+            let unreached = usize::min(self.latest_prompt_index + 1, props.prompts.len());
+            roll_missing_ufo_dice(
+                &props.prompts[current..],
+                self.ufo_rolls.get_mut(current..).unwrap_or_default(),
+                &mut rand::thread_rng(),
+            );
+            if LocalStorage::set(props.storage_key(UFO_ROLLS_KEY), &self.ufo_rolls).is_err() {
+                log::error!("Error saving UFO rolls");
+            }
+            for prompt in props.prompts[unreached..].iter() {
+                match prompt {
+                    TimedPhasePrompt::AssignInterceptors(continent) => {
+                        while self.forces.recall(continent) {}
+                    }
+                    TimedPhasePrompt::ChooseResearch => self.research_choice = None,
+                    _ => {}
+                }
+            }
+            self.save_forces(props);
+            if LocalStorage::set(
+                props.storage_key(RESEARCH_CHOICE_KEY),
+                &self.research_choice,
+            )
+            .is_err()
+            {
+                log::error!("Error saving research choice");
+            }
+            self.latest_prompt_index = props.prompts.len();
+            self.current_prompt_index = self.latest_prompt_index;
+            if LocalStorage::set(
+                props.storage_key(LATEST_PROMPT_INDEX_KEY),
+                self.latest_prompt_index,
+            )
+            .is_err()
+            {
+                log::error!("Error saving latest prompt index");
+            }
+        }
+    }
+
+    fn save_tech_deck(&self, props: &TimedPhaseProps) {
+        if LocalStorage::set(props.storage_key(TECH_DECK_KEY), &self.tech_deck).is_err() {
+            log::error!("Error saving tech deck");
+        }
+    }
 }
 
 impl Component for TimedPhase {
@@ -53,10 +185,39 @@ impl Component for TimedPhase {
             Interval::new(87, move || link.send_message(Msg::Tick))
         };
         // Load when component is created
-        let latest_prompt_index = LocalStorage::get(LATEST_PROMPT_INDEX_KEY).unwrap_or(0);
-        // Add an extra second to let the player re-read the prompts etc
-        let time_remaining_ms =
-            LocalStorage::get(TIME_REMANING_KEY).unwrap_or(STARTING_TIME) + 1_000.0;
+        let latest_prompt_index =
+            LocalStorage::get(ctx.props().storage_key(LATEST_PROMPT_INDEX_KEY)).unwrap_or(0);
+        let timer_expired =
+            LocalStorage::get(ctx.props().storage_key(TIMER_EXPIRED_KEY)).unwrap_or(false);
+        // Add some extra time to let the player re-read the prompts etc, unless time already ran out
+        let time_remaining_ms = LocalStorage::get(ctx.props().storage_key(TIME_REMANING_KEY))
+            .unwrap_or(ctx.props().starting_time_ms)
+            + if timer_expired {
+                0.0
+            } else {
+                ctx.props().reload_grace_ms
+            };
+        let ufo_rolls = LocalStorage::get(ctx.props().storage_key(UFO_ROLLS_KEY))
+            .unwrap_or_else(|_| vec![None; ctx.props().prompts.len()]);
+        let forces = LocalStorage::get(ctx.props().storage_key(FORCES_KEY))
+            .unwrap_or_else(|_| ctx.props().forces.clone());
+        let tech_deck =
+            LocalStorage::get(ctx.props().storage_key(TECH_DECK_KEY)).unwrap_or_else(|_| {
+                let mut tech_deck = ctx.props().tech_deck.clone();
+                if ctx
+                    .props()
+                    .prompts
+                    .contains(&TimedPhasePrompt::ChooseResearch)
+                {
+                    tech_deck.draw();
+                }
+                if LocalStorage::set(ctx.props().storage_key(TECH_DECK_KEY), &tech_deck).is_err() {
+                    log::error!("Error saving tech deck");
+                }
+                tech_deck
+            });
+        let research_choice =
+            LocalStorage::get(ctx.props().storage_key(RESEARCH_CHOICE_KEY)).unwrap_or(None);
 
         Self {
             current_prompt_index: latest_prompt_index,
@@ -66,6 +227,11 @@ impl Component for TimedPhase {
             tick_interval,
             focus: Focus::Prompt,
             prompt_details_ref: NodeRef::default(),
+            ufo_rolls,
+            forces,
+            tech_deck,
+            research_choice,
+            timer_expired,
         }
     }
 
@@ -73,20 +239,17 @@ impl Component for TimedPhase {
         match msg {
             Msg::NextPrompt => {
                 if (self.current_prompt_index + 1) <= ctx.props().prompts.len() {
-                    if matches!(
-                        ctx.props().prompts[self.current_prompt_index],
-                        TimedPhasePrompt::AlienBaseDiscovered(_)
-                    ) {
-                        ctx.props().on_alien_base_discovered.emit(());
-                    }
                     if self.current_prompt_index + 1 > self.latest_prompt_index {
                         self.latest_prompt_index = self.current_prompt_index + 1;
-                        if let Err(_) =
-                            LocalStorage::set(LATEST_PROMPT_INDEX_KEY, self.latest_prompt_index)
+                        if LocalStorage::set(
+                            ctx.props().storage_key(LATEST_PROMPT_INDEX_KEY),
+                            self.latest_prompt_index,
+                        )
+                        .is_err()
                         {
                             log::error!("Error saving latest prompt index");
                         }
-                        self.time_remaining_ms += TIME_PER_PROMPT;
+                        self.time_remaining_ms += ctx.props().time_per_prompt_ms;
                     }
                     self.current_prompt_index += 1;
 
@@ -99,7 +262,7 @@ impl Component for TimedPhase {
                 }
             }
             Msg::PreviousPrompt => {
-                if self.current_prompt_index > 0 {
+                if self.current_prompt_index > 0 && !ctx.props().ironman {
                     self.current_prompt_index -= 1;
                     if let Some(element) = self.prompt_details_ref.cast::<Element>() {
                         element.scroll_to_with_x_and_y(0.0, 0.0);
@@ -109,14 +272,40 @@ impl Component for TimedPhase {
                     false
                 }
             }
+            // Only prompts that have already been seen can be jumped back to
+            Msg::JumpToPrompt(index) => {
+                if index <= self.latest_prompt_index
+                    && index != self.current_prompt_index
+                    && !ctx.props().ironman
+                {
+                    self.current_prompt_index = index;
+                    if let Some(element) = self.prompt_details_ref.cast::<Element>() {
+                        element.scroll_to_with_x_and_y(0.0, 0.0);
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
             Msg::Tick => {
                 let next_tick_time = js_sys::Date::now();
                 let diff = next_tick_time - self.last_tick_time;
-                if matches!(self.focus, Focus::Prompt) && document().has_focus().unwrap_or(false) {
+                if matches!(self.focus, Focus::Prompt)
+                    && (!ctx.props().pause_when_unfocused
+                        || document().has_focus().unwrap_or(false))
+                {
                     self.time_remaining_ms = f64::max(self.time_remaining_ms - diff, 0.0);
-                    if let Err(_) = LocalStorage::set(TIME_REMANING_KEY, self.time_remaining_ms) {
+                    if LocalStorage::set(
+                        ctx.props().storage_key(TIME_REMANING_KEY),
+                        self.time_remaining_ms,
+                    )
+                    .is_err()
+                    {
                         log::error!("Error saving time_remaining");
                     }
+                    if self.time_remaining_ms <= 0.0 && !self.timer_expired {
+                        self.expire_timer(ctx.props());
+                    }
                 }
                 self.last_tick_time = next_tick_time;
                 true
@@ -135,10 +324,65 @@ impl Component for TimedPhase {
                 };
                 true
             }
+            Msg::EnterUFORoll(roll) => {
+                if let Some(entered_roll) = self.ufo_rolls.get_mut(self.current_prompt_index) {
+                    // Selecting the same result again clears it
+                    *entered_roll = (*entered_roll != Some(roll)).as_some(roll);
+                    if LocalStorage::set(ctx.props().storage_key(UFO_ROLLS_KEY), &self.ufo_rolls)
+                        .is_err()
+                    {
+                        log::error!("Error saving UFO rolls");
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::DeployInterceptor(continent) => {
+                let changed = self.forces.deploy(&continent);
+                self.save_forces(ctx.props());
+                changed
+            }
+            Msg::RecallInterceptor(continent) => {
+                let changed = self.forces.recall(&continent);
+                self.save_forces(ctx.props());
+                changed
+            }
+            Msg::UseSatellite => {
+                let changed = self.forces.use_satellite();
+                self.save_forces(ctx.props());
+                changed
+            }
+            Msg::ChooseResearch(tech) => {
+                self.research_choice = tech;
+                if LocalStorage::set(
+                    ctx.props().storage_key(RESEARCH_CHOICE_KEY),
+                    &self.research_choice,
+                )
+                .is_err()
+                {
+                    log::error!("Error saving research choice");
+                }
+                true
+            }
+            Msg::UseTech(tech) => {
+                let changed = self.tech_deck.use_tech(&tech);
+                self.save_tech_deck(ctx.props());
+                changed
+            }
             Msg::OnCompleted => {
-                LocalStorage::delete(LATEST_PROMPT_INDEX_KEY);
-                LocalStorage::delete(TIME_REMANING_KEY);
-                ctx.props().on_completed.emit(());
+                let ufo_tracker = UfoTracker::from_prompts(&ctx.props().prompts, &self.ufo_rolls);
+                if LocalStorage::set(ctx.props().storage_key(UFO_TRACKER_KEY), ufo_tracker).is_err()
+                {
+                    log::error!("Error saving UFO tracker");
+                }
+                let mut tech_deck = self.tech_deck.clone();
+                tech_deck.choose(self.research_choice.as_ref());
+                ctx.props().on_completed.emit(TimedPhaseOutcome {
+                    forces: self.forces.clone(),
+                    tech_deck,
+                    timer_expired: self.timer_expired,
+                });
                 false
             }
         }
@@ -149,9 +393,25 @@ impl Component for TimedPhase {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let phase_ended_early =
+            self.timer_expired && ctx.props().timer_expiry_rule == TimerExpiryRule::EndPhase;
         let (title, next_callback, icons_html, description) = if self.current_prompt_index
             == ctx.props().prompts.len()
+            && phase_ended_early
         {
+            (
+                "Time's Up".to_owned(),
+                ctx.link().callback(|_| Msg::OnCompleted),
+                html! {
+                    <img class="prompt-icon" src="assets/icons/time.png"/>
+                },
+                html! {
+                    <>
+                        {"The timer ran out, so the "}{inline_icon_text_phrase("time", "Timed Phase")}{" is over. Any prompts you hadn't reached resolve by themselves, with their UFO dice rolled for you. Continents you hadn't assigned "}{inline_icon_text_phrase("interceptor", "Interceptors")}{" to get none, and no new research is chosen."}
+                    </>
+                },
+            )
+        } else if self.current_prompt_index == ctx.props().prompts.len() {
             (
                 "Completing Timed Phase".to_owned(),
                 ctx.link().callback(|_| Msg::OnCompleted),
@@ -175,6 +435,67 @@ impl Component for TimedPhase {
         let time_s = (self.time_remaining_ms / 1000.0).floor();
         let time_ms = ((self.time_remaining_ms % 1000.0) / 10.0).floor();
         let is_not_latest_prompt = self.current_prompt_index < self.latest_prompt_index;
+        let prompts = &ctx.props().prompts;
+        let ufo_tracker = UfoTracker::from_prompts(
+            &prompts[..usize::min(self.latest_prompt_index + 1, prompts.len())],
+            &self.ufo_rolls,
+        );
+        let prompt_input = match prompts.get(self.current_prompt_index) {
+            Some(TimedPhasePrompt::RollUFOLocation(_)) => die_input_html(
+                self.ufo_rolls
+                    .get(self.current_prompt_index)
+                    .cloned()
+                    .flatten(),
+                ctx.link().callback(Msg::EnterUFORoll),
+            ),
+            Some(TimedPhasePrompt::AssignInterceptors(continent)) => {
+                let deployed = self.forces.deployed_on(continent);
+                let recall_continent = continent.clone();
+                let deploy_continent = continent.clone();
+                html! {
+                    <div class="ufo-input-container">
+                        <img class="ufo-input-continent-icon" src={format!("assets/icons/{}.png", continent.lowercase())}/>
+                        <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::RecallInterceptor(recall_continent.clone()))} disabled={deployed < 1}>{"-"}</button>
+                        <span class="ufo-input-text" >{ deployed }</span>
+                        <button class="ufo-input-button" onclick={ctx.link().callback(move |_| Msg::DeployInterceptor(deploy_continent.clone()))} disabled={self.forces.reserves() < 1}>{"+"}</button>
+                    </div>
+                }
+            }
+            Some(TimedPhasePrompt::ChooseResearch) => html! {
+                <div class="research-choice-container">
+                    {
+                        self.tech_deck
+                            .drawn()
+                            .iter()
+                            .map(|tech| {
+                                let chosen_tech = tech.clone();
+                                html! {
+                                    <button
+                                        class={classes!("research-choice", (self.research_choice.as_ref() == Some(tech)).as_some("selected"))}
+                                        onclick={ctx.link().callback(move |_| Msg::ChooseResearch(Some(chosen_tech.clone())))}
+                                    >
+                                        <img class="research-choice-icon" src={tech_icon(tech)}/>
+                                        <div>{ format!("{} ({})", tech, tech.cost()) }</div>
+                                    </button>
+                                }
+                            })
+                            .collect::<Html>()
+                    }
+                    <button
+                        class={classes!("research-choice", self.research_choice.is_none().as_some("selected"))}
+                        onclick={ctx.link().callback(|_| Msg::ChooseResearch(None))}
+                    >
+                        {
+                            match self.tech_deck.researching() {
+                                Some(research) => format!("Keep researching {}", research),
+                                None => "Don't research".to_owned(),
+                            }
+                        }
+                    </button>
+                </div>
+            },
+            _ => html! {},
+        };
         html! {
             <>
                 <h1 class={classes!("prompt-title", is_not_latest_prompt.as_some("faded-text"))}>{ title }</h1>
@@ -190,6 +511,9 @@ impl Component for TimedPhase {
                                     <div class={classes!("prompt-description", is_not_latest_prompt.as_some("faded-text"))}>
                                         {description}
                                     </div>
+                                    {tech_hints_html(&self.tech_deck, TechTiming::Timed(prompts.get(self.current_prompt_index)), ctx.link().callback(Msg::UseTech))}
+                                    {prompt_input}
+                                    {ufo_map_html(&ufo_tracker)}
                                 </div>
                             },
                             Focus::TechReference => html!{
@@ -207,13 +531,57 @@ impl Component for TimedPhase {
                         }
                     }
                     <div class="timed-phase-prompt-preview">
+                        {
+                            prompts[..usize::min(self.latest_prompt_index + 1, prompts.len())]
+                                .iter()
+                                .enumerate()
+                                .map(|(index, prompt)| html! {
+                                    <button
+                                        class={classes!("prompt-preview-item", (index == self.current_prompt_index).as_some("selected"))}
+                                        title={prompt.title()}
+                                        onclick={ctx.link().callback(move |_| Msg::JumpToPrompt(index))}
+                                        disabled={!matches!(self.focus, Focus::Prompt) || phase_ended_early || ctx.props().ironman}
+                                    >
+                                        {icon_html_for_prompt(prompt)}
+                                    </button>
+                                })
+                                .collect::<Html>()
+                        }
+                        {
+                            match prompts.get(self.latest_prompt_index + 1) {
+                                Some(next_prompt) if ctx.props().preview_next_prompt && !phase_ended_early => html! {
+                                    <div class="prompt-preview-item face-down" title="Next prompt">
+                                        <img class="prompt-preview-category" src={category_icon_for_prompt(next_prompt)}/>
+                                    </div>
+                                },
+                                _ => html! {},
+                            }
+                        }
                     </div>
                 </div>
                 <div class="bottom-panel">
-                    <button class="button-back" onclick={ctx.link().callback(|_| Msg::PreviousPrompt)} disabled={ !matches!(self.focus, Focus::Prompt) || self.current_prompt_index < 1 }>{ "Back" }</button>
+                    <button class="button-back" onclick={ctx.link().callback(|_| Msg::PreviousPrompt)} disabled={ !matches!(self.focus, Focus::Prompt) || self.current_prompt_index < 1 || phase_ended_early || ctx.props().ironman }>{ "Back" }</button>
                     <div>
                         <div class="round">{format!("Round {}", ctx.props().round)}</div>
                         <div class={classes!("timer", (time_s < 5.0).as_some("blink-red"))}>{ format!("{:3.0}:{:02.0}", time_s, time_ms) }</div>
+                        {
+                            if self.timer_expired {
+                                html! {
+                                    <div class="timer-expired">
+                                        {
+                                            match ctx.props().timer_expiry_rule {
+                                                TimerExpiryRule::IncreasePanic => format!("Time's up! Panic +{}", TIMER_EXPIRY_PANIC),
+                                                _ => "Time's up!".to_owned(),
+                                            }
+                                        }
+                                    </div>
+                                }
+                            } else {
+                                html! {}
+                            }
+                        }
+                        {forces_html(&self.forces, Some(ctx.link().callback(|_| Msg::UseSatellite)))}
+                        {research_html(&self.tech_deck)}
                     </div>
                     <button class="button-done" onclick={next_callback} disabled={ !matches!(self.focus, Focus::Prompt) }>{
                         if is_not_latest_prompt {
@@ -228,7 +596,7 @@ impl Component for TimedPhase {
     }
 }
 
-fn icon_html_for_prompt(prompt: &TimedPhasePrompt) -> Html {
+pub(crate) fn icon_html_for_prompt(prompt: &TimedPhasePrompt) -> Html {
     match prompt {
         TimedPhasePrompt::TakeIncome(_) => html! {
             <img class="prompt-icon" src="assets/icons/income.png"/>
@@ -291,7 +659,22 @@ fn icon_html_for_prompt(prompt: &TimedPhasePrompt) -> Html {
     }
 }
 
-fn description_html_for_prompt(prompt: &TimedPhasePrompt) -> Html {
+// A broad hint at what a prompt is about, without giving away the details
+fn category_icon_for_prompt(prompt: &TimedPhasePrompt) -> &'static str {
+    match prompt {
+        TimedPhasePrompt::TakeIncome(_) => "assets/icons/income.png",
+        TimedPhasePrompt::RollUFOLocation(_)
+        | TimedPhasePrompt::AddUFOsToLocation(_, _)
+        | TimedPhasePrompt::SwapUFOLocations(_, _) => "assets/icons/ufo.png",
+        TimedPhasePrompt::ChooseResearch | TimedPhasePrompt::SetResearchBudget => {
+            "assets/icons/research.png"
+        }
+        TimedPhasePrompt::AssignInterceptors(_) => "assets/icons/interceptor.png",
+        TimedPhasePrompt::AlienBaseDiscovered(_) => "assets/icons/alien-base.png",
+    }
+}
+
+pub(crate) fn description_html_for_prompt(prompt: &TimedPhasePrompt) -> Html {
     match prompt {
         TimedPhasePrompt::TakeIncome(n) => html! {
             <>
@@ -0,0 +1,52 @@
+use boolinator::Boolinator;
+use xcom_1_card::{UfoTracker, ALL_CONTINENTS, MAX_UFOS_PER_CONTINENT};
+use yew::prelude::*;
+
+// Written when the timed phase completes so the resolution phase can use it
+pub const UFO_TRACKER_KEY: &str = "UfoTracker";
+
+pub fn ufo_map_html(tracker: &UfoTracker) -> Html {
+    html! {
+        <div class="ufo-map">
+            {
+                ALL_CONTINENTS
+                    .iter()
+                    .map(|continent| html! {
+                        <div class="ufo-map-continent">
+                            <img class="ufo-map-icon" src={format!("assets/icons/{}.png", continent.lowercase())}/>
+                            <div class="ufo-map-count">
+                                <img class="inline-icon" src="assets/icons/ufo.png"/>
+                                {
+                                    tracker
+                                        .ufos(continent)
+                                        .map(|ufos| format!(" {}", ufos))
+                                        .unwrap_or_else(|| " ?".to_owned())
+                                }
+                            </div>
+                        </div>
+                    })
+                    .collect::<Html>()
+            }
+        </div>
+    }
+}
+
+pub fn die_input_html(roll: Option<u32>, on_roll: Callback<u32>) -> Html {
+    html! {
+        <div class="die-input-container">
+            <div class="die-input-title">{ "Die result (optional):" }</div>
+            {
+                (1..=MAX_UFOS_PER_CONTINENT)
+                    .map(|value| html! {
+                        <button
+                            class={classes!("die-input-button", (roll == Some(value)).as_some("selected"))}
+                            onclick={on_roll.reform(move |_| value)}
+                        >
+                            { value }
+                        </button>
+                    })
+                    .collect::<Html>()
+            }
+        </div>
+    }
+}
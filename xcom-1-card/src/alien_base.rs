@@ -0,0 +1,87 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Continent;
+
+// Success tokens needed to destroy the alien base
+pub const ALIEN_BASE_HEALTH: u32 = 3;
+
+// The alien base, once it has been discovered, and the success tokens added to it during UFO
+//   defence in earlier rounds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlienBase {
+    continent: Continent,
+    damage: u32,
+}
+
+impl AlienBase {
+    pub fn new(continent: Continent) -> Self {
+        Self {
+            continent,
+            damage: 0,
+        }
+    }
+
+    pub fn continent(&self) -> &Continent {
+        &self.continent
+    }
+
+    pub fn damage(&self) -> u32 {
+        self.damage
+    }
+
+    // How many more success tokens it takes to destroy the base
+    pub fn remaining_health(&self) -> u32 {
+        // Saves may hold more damage than the base has health
+        ALIEN_BASE_HEALTH.saturating_sub(self.damage)
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.damage >= ALIEN_BASE_HEALTH
+    }
+
+    // Returns how many success tokens were added before the base was destroyed
+    pub fn add_damage(&mut self, damage: u32) -> u32 {
+        let added = damage.min(self.remaining_health());
+        self.damage += added;
+        added
+    }
+}
+
+impl Display for AlienBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The Alien Base is in {} ({}/{} damage)",
+            &self.continent, self.damage, ALIEN_BASE_HEALTH
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn add_damage_test() {
+        let mut alien_base = AlienBase::new(Continent::Eurasia);
+        assert_eq!(1, alien_base.add_damage(1));
+        assert_eq!(
+            "The Alien Base is in Eurasia (1/3 damage)",
+            alien_base.to_string()
+        );
+        assert_eq!(2, alien_base.add_damage(4));
+        assert!(alien_base.is_destroyed());
+        assert_eq!(0, alien_base.remaining_health());
+    }
+
+    #[test]
+    fn excess_saved_damage_test() {
+        let mut alien_base: AlienBase =
+            serde_json::from_str(r#"{ "continent": "Africa", "damage": 5 }"#).unwrap();
+        assert_eq!(0, alien_base.remaining_health());
+        assert!(alien_base.is_destroyed());
+        assert_eq!(0, alien_base.add_damage(1));
+    }
+}
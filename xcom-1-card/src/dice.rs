@@ -0,0 +1,269 @@
+use std::fmt::Display;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Continent, Tech, TechDeck};
+
+pub const SUCCESS_DIE_SIDES: u32 = 6;
+// Using D6s, a 5 or 6 counts as a success
+pub const SUCCESS_DIE_THRESHOLD: u32 = 5;
+pub const ALIEN_DIE_SIDES: u32 = 8;
+pub const MIN_ALIEN_THREAT: u32 = 1;
+pub const MAX_ALIEN_THREAT: u32 = 5;
+
+// Each continent is a separate UFO defence task, so the alien threat resets between them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Task {
+    Research,
+    UFODefence(Continent),
+}
+
+impl Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Research => write!(f, "Research"),
+            Self::UFODefence(continent) => write!(f, "UFO Defence ({})", continent),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRoll {
+    pub success_dice: Vec<u32>,
+    pub alien_die: u32,
+    // The alien threat level this attempt was rolled against
+    pub alien_threat: u32,
+    pub carapace_armour_used: bool,
+    pub emp_cannon_used: bool,
+}
+
+impl TaskRoll {
+    pub fn successes(&self) -> u32 {
+        self.success_dice
+            .iter()
+            .filter(|die| **die >= SUCCESS_DIE_THRESHOLD)
+            .count() as u32
+    }
+
+    pub fn alien_threat_triggered(&self) -> bool {
+        self.alien_die <= self.alien_threat
+    }
+}
+
+fn roll_success_die(rng: &mut impl Rng) -> u32 {
+    rng.gen_range(1..=SUCCESS_DIE_SIDES)
+}
+
+fn roll_alien_die(rng: &mut impl Rng) -> u32 {
+    rng.gen_range(1..=ALIEN_DIE_SIDES)
+}
+
+// Rolls attempts at tasks during the resolution phase, keeping track of the alien threat
+//   between attempts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRoller {
+    task: Option<Task>,
+    // The alien threat level the next attempt will be rolled against
+    alien_threat: u32,
+    last_roll: Option<TaskRoll>,
+}
+
+impl Default for TaskRoller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskRoller {
+    pub fn new() -> Self {
+        Self {
+            task: None,
+            alien_threat: MIN_ALIEN_THREAT,
+            last_roll: None,
+        }
+    }
+
+    pub fn task(&self) -> Option<&Task> {
+        self.task.as_ref()
+    }
+
+    pub fn alien_threat(&self) -> u32 {
+        self.alien_threat
+    }
+
+    pub fn last_roll(&self) -> Option<&TaskRoll> {
+        self.last_roll.as_ref()
+    }
+
+    // Beginning a new task resets the alien threat to the first space on the track
+    fn start_task(&mut self, task: &Task) {
+        if self.task.as_ref() != Some(task) {
+            self.task = Some(task.clone());
+            self.alien_threat = MIN_ALIEN_THREAT;
+            self.last_roll = None;
+        }
+    }
+
+    // Alien Intel reduces the alien threat by one before rolling, but not below the first space.
+    //   Like the other techs, it's exhausted once used until the next round
+    pub fn use_alien_intel(&mut self, task: &Task, tech_deck: &mut TechDeck) -> bool {
+        self.start_task(task);
+        if self.alien_threat > MIN_ALIEN_THREAT && tech_deck.use_tech(&Tech::AlienIntel) {
+            self.alien_threat -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Rolls an attempt at the task, then increases the alien threat for the next attempt
+    pub fn roll(&mut self, task: &Task, success_dice: u32, rng: &mut impl Rng) -> &TaskRoll {
+        self.start_task(task);
+        let roll = TaskRoll {
+            success_dice: (0..success_dice).map(|_| roll_success_die(rng)).collect(),
+            alien_die: roll_alien_die(rng),
+            alien_threat: self.alien_threat,
+            carapace_armour_used: false,
+            emp_cannon_used: false,
+        };
+        self.alien_threat = (self.alien_threat + 1).min(MAX_ALIEN_THREAT);
+        self.last_roll.insert(roll)
+    }
+
+    // Carapace Armour rerolls the alien die of the last attempt, exhausting it for the round
+    pub fn reroll_alien_die(&mut self, tech_deck: &mut TechDeck, rng: &mut impl Rng) -> bool {
+        match self.last_roll {
+            Some(ref mut roll) if tech_deck.use_tech(&Tech::CarapaceArmour) => {
+                roll.alien_die = roll_alien_die(rng);
+                roll.carapace_armour_used = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // EMP Cannon rerolls any of the success dice of the last attempt, exhausting it for the round
+    pub fn reroll_success_dice(
+        &mut self,
+        dice: &[usize],
+        tech_deck: &mut TechDeck,
+        rng: &mut impl Rng,
+    ) -> bool {
+        match self.last_roll {
+            Some(ref mut roll) if tech_deck.use_tech(&Tech::EMPCannon) => {
+                for index in dice.iter() {
+                    if let Some(die) = roll.success_dice.get_mut(*index) {
+                        *die = roll_success_die(rng);
+                    }
+                }
+                roll.emp_cannon_used = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use test_case::test_case;
+
+    #[test_case(vec![1, 4, 5, 6], 2)]
+    #[test_case(vec![], 0)]
+    fn successes_test(success_dice: Vec<u32>, expected: u32) {
+        let roll = TaskRoll {
+            success_dice,
+            alien_die: 8,
+            alien_threat: 1,
+            carapace_armour_used: false,
+            emp_cannon_used: false,
+        };
+        assert_eq!(expected, roll.successes());
+    }
+
+    #[test_case(3, 3, true)]
+    #[test_case(4, 3, false)]
+    fn alien_threat_triggered_test(alien_die: u32, alien_threat: u32, expected: bool) {
+        let roll = TaskRoll {
+            success_dice: vec![],
+            alien_die,
+            alien_threat,
+            carapace_armour_used: false,
+            emp_cannon_used: false,
+        };
+        assert_eq!(expected, roll.alien_threat_triggered());
+    }
+
+    #[test]
+    fn alien_threat_increases_and_resets_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut roller = TaskRoller::new();
+        let task = Task::UFODefence(Continent::America);
+        for attempt in 0..7 {
+            let roll = roller.roll(&task, 3, &mut rng);
+            assert_eq!(
+                (MIN_ALIEN_THREAT + attempt).min(MAX_ALIEN_THREAT),
+                roll.alien_threat
+            );
+            assert_eq!(3, roll.success_dice.len());
+        }
+        assert_eq!(MAX_ALIEN_THREAT, roller.alien_threat());
+
+        let next_task = Task::UFODefence(Continent::Africa);
+        assert_eq!(
+            MIN_ALIEN_THREAT,
+            roller.roll(&next_task, 2, &mut rng).alien_threat
+        );
+    }
+
+    #[test]
+    fn alien_intel_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut roller = TaskRoller::new();
+        let mut tech_deck = TechDeck::owning(vec![Tech::AlienIntel]);
+        assert!(!roller.use_alien_intel(&Task::Research, &mut tech_deck));
+        roller.roll(&Task::Research, 1, &mut rng);
+        roller.roll(&Task::Research, 1, &mut rng);
+        assert!(roller.use_alien_intel(&Task::Research, &mut tech_deck));
+        // Exhausted until the next round
+        assert!(!roller.use_alien_intel(&Task::Research, &mut tech_deck));
+        assert_eq!(2, roller.roll(&Task::Research, 1, &mut rng).alien_threat);
+    }
+
+    #[test]
+    fn rerolls_once_per_round_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut roller = TaskRoller::new();
+        let mut tech_deck = TechDeck::owning(vec![Tech::CarapaceArmour, Tech::EMPCannon]);
+        assert!(!roller.reroll_alien_die(&mut tech_deck, &mut rng));
+        let first_die = roller.roll(&Task::Research, 4, &mut rng).success_dice[0];
+        assert!(roller.reroll_success_dice(&[1, 2, 3], &mut tech_deck, &mut rng));
+        assert!(!roller.reroll_success_dice(&[0], &mut tech_deck, &mut rng));
+        assert!(roller.reroll_alien_die(&mut tech_deck, &mut rng));
+        assert!(!roller.reroll_alien_die(&mut tech_deck, &mut rng));
+
+        let roll = roller.last_roll().unwrap();
+        assert_eq!(first_die, roll.success_dice[0]);
+        assert!(roll.carapace_armour_used && roll.emp_cannon_used);
+
+        // Still exhausted on the next attempt
+        roller.roll(&Task::Research, 4, &mut rng);
+        assert!(!roller.reroll_alien_die(&mut tech_deck, &mut rng));
+        tech_deck.refresh();
+        assert!(roller.reroll_alien_die(&mut tech_deck, &mut rng));
+    }
+
+    #[test]
+    fn techs_must_be_owned_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut roller = TaskRoller::new();
+        let mut tech_deck = TechDeck::owning(vec![Tech::AlienIntel]);
+        roller.roll(&Task::Research, 2, &mut rng);
+        assert!(!roller.reroll_alien_die(&mut tech_deck, &mut rng));
+        assert!(!roller.reroll_success_dice(&[0], &mut tech_deck, &mut rng));
+        assert!(!tech_deck.is_exhausted(&Tech::CarapaceArmour));
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{PanicLevel, PanicLevelInput, PanicTrack};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Impossible,
+}
+
+pub const ALL_DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Normal,
+    Difficulty::Hard,
+    Difficulty::Impossible,
+];
+
+impl Difficulty {
+    pub fn lowercase(&self) -> String {
+        match self {
+            Self::Easy => "easy",
+            Self::Normal => "normal",
+            Self::Hard => "hard",
+            Self::Impossible => "impossible",
+        }
+        .to_owned()
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Easy => {
+                "More funding, more time and a slower invasion. Recommended for your first games."
+            }
+            Self::Normal => "The invasion as intended.",
+            Self::Hard => "Tighter budgets, less time and the UFOs arrive sooner.",
+            Self::Impossible => "Humanity's last stand. Good luck, Commander.",
+        }
+        .to_owned()
+    }
+
+    // Income drops as the marker moves into each new level of the panic track. Once it reaches the
+    //   alien space the game is lost, so that pays the same as red
+    pub fn base_income(&self, panic: &PanicTrack) -> i32 {
+        let (yellow, orange, red) = match self {
+            Self::Easy => (7, 6, 5),
+            Self::Normal => (6, 5, 4),
+            Self::Hard => (6, 4, 3),
+            Self::Impossible => (5, 4, 2),
+        };
+        match panic.level() {
+            PanicLevelInput::PanicLevel(PanicLevel::Yellow) => yellow,
+            PanicLevelInput::PanicLevel(PanicLevel::Orange) => orange,
+            PanicLevelInput::PanicLevel(PanicLevel::Red) | PanicLevelInput::AlienSpace => red,
+        }
+    }
+
+    // Weights for the random income adjustments of -1, 0 and +1 respectively
+    pub fn income_adjustment_weights(&self) -> [f64; 3] {
+        match self {
+            Self::Easy => [0.20, 0.50, 0.30],
+            Self::Normal => [0.30, 0.50, 0.20],
+            Self::Hard => [0.40, 0.45, 0.15],
+            Self::Impossible => [0.50, 0.40, 0.10],
+        }
+    }
+
+    // The round used to decide which bonus UFO prompts are added, so harder difficulties
+    //   escalate earlier and easier ones later
+    pub fn escalation_round(&self, round: u32) -> u32 {
+        match self {
+            Self::Easy => round.saturating_sub(1),
+            Self::Normal => round,
+            Self::Hard => round + 1,
+            Self::Impossible => round + 2,
+        }
+    }
+
+    // How many times prompts are shuffled out of order based on the UFOs left on the map last round
+    pub fn num_shifts(&self, leftover_ufos: u32) -> u32 {
+        let shifts = match self {
+            Self::Easy => [0, 2, 3, 5, 7, 9, 12],
+            Self::Normal => [0, 3, 5, 7, 9, 12, 15],
+            Self::Hard => [0, 4, 6, 9, 12, 15, 18],
+            Self::Impossible => [0, 5, 8, 11, 14, 17, 20],
+        };
+        let bracket = match leftover_ufos {
+            n if n < 1 => 0,
+            n if n < 2 => 1,
+            n if n < 4 => 2,
+            n if n < 6 => 3,
+            n if n < 9 => 4,
+            n if n < 13 => 5,
+            _ => 6,
+        };
+        shifts[bracket]
+    }
+
+    pub fn timed_phase_starting_time_ms(&self) -> f64 {
+        match self {
+            Self::Easy => 20_000.0,
+            Self::Normal => 16_000.0,
+            Self::Hard => 14_000.0,
+            Self::Impossible => 12_000.0,
+        }
+    }
+
+    pub fn timed_phase_time_per_prompt_ms(&self) -> f64 {
+        match self {
+            Self::Easy => 10_000.0,
+            Self::Normal => 8_000.0,
+            Self::Hard => 7_000.0,
+            Self::Impossible => 6_000.0,
+        }
+    }
+
+    pub fn alien_base_discovery_round(&self) -> u32 {
+        match self {
+            Self::Easy => 4,
+            Self::Normal => 5,
+            Self::Hard => 6,
+            Self::Impossible => 7,
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Easy => write!(f, "Easy"),
+            Self::Normal => write!(f, "Normal"),
+            Self::Hard => write!(f, "Hard"),
+            Self::Impossible => write!(f, "Impossible"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ALIEN_PANIC_SPACE;
+    use test_case::test_case;
+
+    #[test_case(0, 6)]
+    #[test_case(1, 6)]
+    #[test_case(2, 5)]
+    #[test_case(4, 5)]
+    #[test_case(5, 4)]
+    #[test_case(6, 4)]
+    fn normal_income_test(panic_space: u32, expected: i32) {
+        assert_eq!(
+            expected,
+            Difficulty::Normal.base_income(&PanicTrack::new(panic_space))
+        );
+    }
+
+    #[test]
+    fn harder_difficulties_are_harder_test() {
+        for pair in ALL_DIFFICULTIES.windows(2) {
+            let (easier, harder) = (pair[0], pair[1]);
+            for panic in (0..ALIEN_PANIC_SPACE).map(PanicTrack::new) {
+                assert!(easier.base_income(&panic) >= harder.base_income(&panic));
+            }
+            for leftover_ufos in 0..20 {
+                assert!(easier.num_shifts(leftover_ufos) <= harder.num_shifts(leftover_ufos));
+            }
+            assert!(easier.escalation_round(5) < harder.escalation_round(5));
+            assert!(easier.timed_phase_starting_time_ms() > harder.timed_phase_starting_time_ms());
+            assert!(easier.alien_base_discovery_round() < harder.alien_base_discovery_round());
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_ESCALATION_SCHEDULE: &str = include_str!("../escalation.json");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EscalationEvent {
+    AddUFOs(i32),
+    SwapUFOs,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationStep {
+    pub from_round: u32,
+    pub event: EscalationEvent,
+    // If set, the event happens again every this many rounds after `from_round`
+    #[serde(default)]
+    pub repeat_every: Option<u32>,
+}
+
+impl EscalationStep {
+    fn occurrences(&self, round: u32) -> u32 {
+        if round < self.from_round {
+            return 0;
+        }
+        match self.repeat_every {
+            Some(every) if every > 0 => 1 + (round - self.from_round) / every,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationSchedule {
+    pub steps: Vec<EscalationStep>,
+}
+
+impl EscalationSchedule {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn events_for_round(&self, round: u32) -> Vec<EscalationEvent> {
+        self.steps
+            .iter()
+            .flat_map(|step| (0..step.occurrences(round)).map(move |_| step.event.clone()))
+            .collect()
+    }
+}
+
+impl Default for EscalationSchedule {
+    fn default() -> Self {
+        Self::from_json(DEFAULT_ESCALATION_SCHEDULE)
+            .expect("Default escalation schedule should be valid")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(1, 0)]
+    #[test_case(2, 1)]
+    #[test_case(5, 4)]
+    #[test_case(8, 7)]
+    #[test_case(9, 7)]
+    fn default_schedule_matches_original_rounds_test(round: u32, expected_events: usize) {
+        assert_eq!(
+            expected_events,
+            EscalationSchedule::default().events_for_round(round).len()
+        );
+    }
+
+    #[test]
+    fn default_schedule_keeps_escalating_test() {
+        let schedule = EscalationSchedule::default();
+        let mut previous = schedule.events_for_round(9).len();
+        for round in (11..30).step_by(2) {
+            let events = schedule.events_for_round(round).len();
+            assert!(events > previous);
+            previous = events;
+        }
+    }
+
+    #[test_case(4, 0)]
+    #[test_case(5, 1)]
+    #[test_case(6, 1)]
+    #[test_case(7, 2)]
+    #[test_case(11, 4)]
+    fn repeating_step_test(round: u32, expected_events: usize) {
+        let schedule = EscalationSchedule::from_json(
+            r#"{ "steps": [{ "from_round": 5, "event": "SwapUFOs", "repeat_every": 2 }] }"#,
+        )
+        .unwrap();
+        assert_eq!(expected_events, schedule.events_for_round(round).len());
+    }
+
+    #[test]
+    fn invalid_schedule_test() {
+        assert!(
+            EscalationSchedule::from_json(r#"{ "steps": [{ "event": "SwapUFOs" }] }"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardState, Continent, ResolutionPhasePrompt, ResolvesPrompts, ALL_CONTINENTS};
+
+pub const STARTING_RESERVES: u32 = 8;
+pub const STARTING_SATELLITES: u32 = 2;
+pub const MAX_SATELLITES: u32 = 3;
+
+// Interceptors in the player's reserves and deployed to each continent, plus the satellite
+//   track. Interceptors that are shot down go back to the supply, so aren't counted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Forces {
+    reserves: u32,
+    // In the same order as ALL_CONTINENTS
+    deployed: [u32; 3],
+    satellites: u32,
+}
+
+impl Default for Forces {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Forces {
+    pub fn new() -> Self {
+        Self {
+            reserves: STARTING_RESERVES,
+            deployed: [0; 3],
+            satellites: STARTING_SATELLITES,
+        }
+    }
+
+    pub fn reserves(&self) -> u32 {
+        self.reserves
+    }
+
+    pub fn deployed_on(&self, continent: &Continent) -> u32 {
+        self.deployed[continent.index()]
+    }
+
+    pub fn total_deployed(&self) -> u32 {
+        self.deployed.iter().sum()
+    }
+
+    pub fn satellites(&self) -> u32 {
+        self.satellites
+    }
+
+    // Moves an interceptor from the reserves to the continent, if there are any left
+    pub fn deploy(&mut self, continent: &Continent) -> bool {
+        if self.reserves > 0 {
+            self.reserves -= 1;
+            self.deployed[continent.index()] += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Moves an interceptor from the continent back to the reserves
+    pub fn recall(&mut self, continent: &Continent) -> bool {
+        let deployed = &mut self.deployed[continent.index()];
+        if *deployed > 0 {
+            *deployed -= 1;
+            self.reserves += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Interceptors shot down during UFO defence go back to the supply rather than the reserves.
+    //   Returns how many were actually removed
+    pub fn lose_interceptors(&mut self, continent: &Continent, interceptors: u32) -> u32 {
+        let deployed = &mut self.deployed[continent.index()];
+        let lost = interceptors.min(*deployed);
+        *deployed -= lost;
+        lost
+    }
+
+    pub fn add_interceptors(&mut self, interceptors: u32) {
+        self.reserves += interceptors;
+    }
+
+    // How many more satellites the track has room for
+    pub fn satellite_space(&self) -> u32 {
+        MAX_SATELLITES - self.satellites
+    }
+
+    // Returns how many satellites were added before reaching the top of the track
+    pub fn add_satellites(&mut self, satellites: u32) -> u32 {
+        let added = satellites.min(self.satellite_space());
+        self.satellites += added;
+        added
+    }
+
+    pub fn use_satellite(&mut self) -> bool {
+        if self.satellites > 0 {
+            self.satellites -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn clean_up(&mut self) {
+        self.reserves += self.total_deployed();
+        self.deployed = [0; 3];
+    }
+}
+
+impl ResolvesPrompts for Forces {
+    // Applies the changes a resolution phase prompt makes to the forces
+    fn resolve_prompt(&mut self, prompt: &ResolutionPhasePrompt, board_state: &BoardState) {
+        match prompt {
+            ResolutionPhasePrompt::ResolveUFODefence => {
+                for continent in ALL_CONTINENTS.iter() {
+                    self.lose_interceptors(
+                        continent,
+                        board_state.interceptors_lost[continent.index()],
+                    );
+                }
+            }
+            ResolutionPhasePrompt::ResolveContinentBonuses
+                if board_state
+                    .earned_continent_bonuses()
+                    .contains(&Continent::America) =>
+            {
+                self.add_interceptors(1);
+                self.add_satellites(1);
+            }
+            ResolutionPhasePrompt::CleanUp => self.clean_up(),
+            // The game limits purchases to what can be paid for and has room before they get here
+            ResolutionPhasePrompt::PurchaseReplacementForces => {
+                self.add_interceptors(board_state.spending.interceptors_purchased);
+                self.add_satellites(board_state.spending.satellites_purchased);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Display for Forces {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Reserves: {}, Deployed: {}, Satellites: {}/{}",
+            self.reserves,
+            ALL_CONTINENTS
+                .iter()
+                .map(|continent| format!("{} {}", continent, self.deployed_on(continent)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.satellites,
+            MAX_SATELLITES
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RoundSpending;
+
+    #[test]
+    fn deploy_and_recall_test() {
+        let mut forces = Forces::new();
+        for _ in 0..3 {
+            assert!(forces.deploy(&Continent::Africa));
+        }
+        assert!(forces.recall(&Continent::Africa));
+        assert!(!forces.recall(&Continent::Eurasia));
+        assert_eq!(2, forces.deployed_on(&Continent::Africa));
+        assert_eq!(STARTING_RESERVES - 2, forces.reserves());
+        while forces.deploy(&Continent::America) {}
+        assert_eq!(0, forces.reserves());
+        assert_eq!(STARTING_RESERVES, forces.total_deployed());
+    }
+
+    #[test]
+    fn satellite_cap_test() {
+        let mut forces = Forces::new();
+        assert_eq!(1, forces.add_satellites(2));
+        assert_eq!(MAX_SATELLITES, forces.satellites());
+        assert!(forces.use_satellite());
+        assert_eq!(1, forces.satellite_space());
+    }
+
+    #[test]
+    fn resolve_round_test() {
+        let mut forces = Forces::new();
+        forces.use_satellite();
+        for _ in 0..4 {
+            forces.deploy(&Continent::America);
+        }
+        forces.deploy(&Continent::Eurasia);
+        forces.resolve_round(&BoardState {
+            ufos_left: [0, 1, 1],
+            spending: RoundSpending {
+                interceptors_purchased: 1,
+                satellites_purchased: 2,
+                ..RoundSpending::default()
+            },
+            interceptors_lost: [2, 0, 3],
+            ..BoardState::default()
+        });
+        // Only one interceptor was on Eurasia, so 8 - 3 shot down + 1 from America + 1 purchased
+        assert_eq!(7, forces.reserves());
+        assert_eq!(0, forces.total_deployed());
+        assert_eq!(MAX_SATELLITES, forces.satellites());
+    }
+}
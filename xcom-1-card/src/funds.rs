@@ -0,0 +1,323 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardState, Continent, TimedPhasePrompt};
+
+pub const STARTING_FUNDS: u32 = 5;
+pub const INTERCEPTOR_PRICE: u32 = 1;
+pub const SATELLITE_PRICE: u32 = 2;
+pub const AFRICA_BONUS_FUNDS: u32 = 2;
+pub const ELERIUM_GENERATOR_FUNDS: u32 = 2;
+
+// What the player reports about money spent and gained during a round, besides income and
+//   continent bonuses which are worked out from the prompts and board
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundSpending {
+    pub elerium_generator_used: bool,
+    pub interceptors_deployed: u32,
+    pub research_budget: u32,
+    pub interceptors_purchased: u32,
+    pub satellites_purchased: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FundsTransaction {
+    StartingFunds,
+    Income,
+    EleriumGenerator,
+    AuditSpending { cost: u32, unpaid: u32 },
+    AfricaBonus,
+    PurchaseInterceptors(u32),
+    PurchaseSatellites(u32),
+}
+
+impl Display for FundsTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StartingFunds => write!(f, "Starting funds"),
+            Self::Income => write!(f, "Income"),
+            Self::EleriumGenerator => write!(f, "Elerium Generator"),
+            Self::AuditSpending { cost, unpaid: 0 } => write!(f, "Audit (§{} owed)", cost),
+            Self::AuditSpending { cost, unpaid } => {
+                write!(f, "Audit (§{} owed, §{} unpaid)", cost, unpaid)
+            }
+            Self::AfricaBonus => write!(f, "Africa bonus"),
+            Self::PurchaseInterceptors(amount) => write!(f, "Bought {} Interceptor(s)", amount),
+            Self::PurchaseSatellites(amount) => write!(f, "Bought {} Satellite(s)", amount),
+        }
+    }
+}
+
+// A single change to the funds. The amount is what actually changed hands, so e.g. an audit
+//   that couldn't be paid in full only takes what was available
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FundsEntry {
+    pub round: u32,
+    pub transaction: FundsTransaction,
+    pub amount: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Audit {
+    pub cost: u32,
+    pub paid: u32,
+    // Each unpaid §1 increases the panic track one space
+    pub unpaid: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FundsLedger {
+    entries: Vec<FundsEntry>,
+}
+
+impl Default for FundsLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FundsLedger {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![FundsEntry {
+                round: 0,
+                transaction: FundsTransaction::StartingFunds,
+                amount: STARTING_FUNDS as i32,
+            }],
+        }
+    }
+
+    pub fn entries(&self) -> &[FundsEntry] {
+        &self.entries
+    }
+
+    pub fn balance(&self) -> u32 {
+        self.entries
+            .iter()
+            .map(|entry| entry.amount)
+            .sum::<i32>()
+            .max(0) as u32
+    }
+
+    fn record(&mut self, round: u32, transaction: FundsTransaction, amount: i32) {
+        // Funds can't go below zero, so only record what could actually be taken
+        let amount = amount.max(-(self.balance() as i32));
+        self.entries.push(FundsEntry {
+            round,
+            transaction,
+            amount,
+        });
+    }
+
+    pub fn take_income(&mut self, round: u32, prompts: &[TimedPhasePrompt]) {
+        for prompt in prompts.iter() {
+            if let TimedPhasePrompt::TakeIncome(income) = prompt {
+                self.record(round, FundsTransaction::Income, *income);
+            }
+        }
+    }
+
+    // How much of the spending the funds will cover at the start of the resolution phase,
+    //   without changing the ledger
+    pub fn audit(&self, spending: &RoundSpending) -> Audit {
+        let available = if spending.elerium_generator_used {
+            self.balance() + ELERIUM_GENERATOR_FUNDS
+        } else {
+            self.balance()
+        };
+        let cost = spending.interceptors_deployed + spending.research_budget;
+        let paid = cost.min(available);
+        Audit {
+            cost,
+            paid,
+            unpaid: cost - paid,
+        }
+    }
+
+    // Applies the round's spending and gains in the order the resolution phase handles them,
+    //   returning the audit so any unpaid funds can be added to the panic track
+    pub fn resolve_round(&mut self, round: u32, board_state: &BoardState) -> Audit {
+        let spending = &board_state.spending;
+        let audit = self.audit(spending);
+        if spending.elerium_generator_used {
+            self.record(
+                round,
+                FundsTransaction::EleriumGenerator,
+                ELERIUM_GENERATOR_FUNDS as i32,
+            );
+        }
+
+        self.record(
+            round,
+            FundsTransaction::AuditSpending {
+                cost: audit.cost,
+                unpaid: audit.unpaid,
+            },
+            -(audit.paid as i32),
+        );
+
+        if board_state
+            .earned_continent_bonuses()
+            .contains(&Continent::Africa)
+        {
+            self.record(
+                round,
+                FundsTransaction::AfricaBonus,
+                AFRICA_BONUS_FUNDS as i32,
+            );
+        }
+
+        // Purchases have already been limited by the game to what can be paid for and what the
+        //   forces have room for
+        let interceptors = spending.interceptors_purchased;
+        if interceptors > 0 {
+            self.record(
+                round,
+                FundsTransaction::PurchaseInterceptors(interceptors),
+                -((interceptors * INTERCEPTOR_PRICE) as i32),
+            );
+        }
+        let satellites = spending.satellites_purchased;
+        if satellites > 0 {
+            self.record(
+                round,
+                FundsTransaction::PurchaseSatellites(satellites),
+                -((satellites * SATELLITE_PRICE) as i32),
+            );
+        }
+
+        audit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn board_state(ufos_left: [u32; 3], spending: RoundSpending) -> BoardState {
+        BoardState {
+            ufos_left,
+            spending,
+            ..BoardState::default()
+        }
+    }
+
+    #[test]
+    fn take_income_test() {
+        let mut funds = FundsLedger::new();
+        funds.take_income(
+            1,
+            &[
+                TimedPhasePrompt::TakeIncome(6),
+                TimedPhasePrompt::ChooseResearch,
+            ],
+        );
+        assert_eq!(STARTING_FUNDS + 6, funds.balance());
+    }
+
+    #[test_case(3, 1, Audit { cost: 4, paid: 4, unpaid: 0 })]
+    #[test_case(4, 2, Audit { cost: 6, paid: 5, unpaid: 1 })]
+    #[test_case(0, 0, Audit { cost: 0, paid: 0, unpaid: 0 })]
+    fn audit_test(interceptors_deployed: u32, research_budget: u32, expected: Audit) {
+        let mut funds = FundsLedger::new();
+        let spending = RoundSpending {
+            interceptors_deployed,
+            research_budget,
+            ..RoundSpending::default()
+        };
+        assert_eq!(expected, funds.audit(&spending));
+        assert_eq!(
+            expected,
+            funds.resolve_round(1, &board_state([1, 1, 1], spending))
+        );
+        assert_eq!(STARTING_FUNDS - expected.paid, funds.balance());
+    }
+
+    #[test]
+    fn resolve_round_test() {
+        let mut funds = FundsLedger::new();
+        funds.take_income(1, &[TimedPhasePrompt::TakeIncome(4)]);
+        let audit = funds.resolve_round(
+            1,
+            &board_state(
+                [1, 0, 2],
+                RoundSpending {
+                    elerium_generator_used: true,
+                    interceptors_deployed: 6,
+                    research_budget: 2,
+                    interceptors_purchased: 1,
+                    satellites_purchased: 1,
+                },
+            ),
+        );
+        // 5 + 4 income + 2 elerium - 8 audit + 2 africa - 1 interceptor - 2 satellite
+        assert_eq!(0, audit.unpaid);
+        assert_eq!(2, funds.balance());
+        assert_eq!(
+            vec![
+                FundsTransaction::StartingFunds,
+                FundsTransaction::Income,
+                FundsTransaction::EleriumGenerator,
+                FundsTransaction::AuditSpending { cost: 8, unpaid: 0 },
+                FundsTransaction::AfricaBonus,
+                FundsTransaction::PurchaseInterceptors(1),
+                FundsTransaction::PurchaseSatellites(1),
+            ],
+            funds
+                .entries()
+                .iter()
+                .map(|entry| entry.transaction.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn purchases_test() {
+        let mut funds = FundsLedger::new();
+        funds.resolve_round(
+            1,
+            &board_state(
+                [1, 1, 1],
+                RoundSpending {
+                    interceptors_purchased: 2,
+                    satellites_purchased: 1,
+                    ..RoundSpending::default()
+                },
+            ),
+        );
+        assert_eq!(
+            STARTING_FUNDS - 2 * INTERCEPTOR_PRICE - SATELLITE_PRICE,
+            funds.balance()
+        );
+        assert_eq!(
+            vec![
+                FundsTransaction::PurchaseInterceptors(2),
+                FundsTransaction::PurchaseSatellites(1),
+            ],
+            funds.entries()[2..]
+                .iter()
+                .map(|entry| entry.transaction.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn audit_includes_elerium_generator_test() {
+        let spending = RoundSpending {
+            elerium_generator_used: true,
+            research_budget: 7,
+            ..RoundSpending::default()
+        };
+        let funds = FundsLedger::new();
+        assert_eq!(
+            Audit {
+                cost: 7,
+                paid: 7,
+                unpaid: 0
+            },
+            funds.audit(&spending)
+        );
+    }
+}
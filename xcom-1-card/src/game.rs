@@ -0,0 +1,705 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{
+    generate_timed_phase_prompts, round_rng, AlienBase, Continent, Difficulty, EscalationSchedule,
+    Forces, FundsLedger, GameLog, GameResult, PanicTrack, ResolvesPrompts, RoundLogEntry,
+    RoundSpending, Tech, TechDeck, TimedPhasePrompt, TimerExpiryRule, ALL_CONTINENTS,
+    INTERCEPTOR_PRICE, MAX_UFOS_PER_CONTINENT, SATELLITE_PRICE, TIMER_EXPIRY_PANIC,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameState {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub round: u32,
+    // Where the alien base was found, once it has been discovered
+    pub alien_base: Option<AlienBase>,
+    #[serde(alias = "panic_level")]
+    pub panic: PanicTrack,
+    pub ufos_left: u32,
+    // Where the interceptors are during the current round. Older saves didn't track forces, so
+    //   they carry on from the setup inventory
+    #[serde(default)]
+    pub forces: Forces,
+    pub tech_deck: TechDeck,
+    #[serde(default)]
+    pub timer_expiry_rule: TimerExpiryRule,
+    // Ironman games can't go back to earlier prompts or take back the end of the game
+    #[serde(default)]
+    pub ironman: bool,
+}
+
+// What the player reports about the board during the resolution phase. The default is a round
+//   where nothing happened, for filling in the rest of a partial report
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardState {
+    #[serde(alias = "panic_level")]
+    pub panic: PanicTrack,
+    // UFOs left on each continent after UFO defence, in the same order as ALL_CONTINENTS
+    #[serde(deserialize_with = "deserialize_ufos_left")]
+    pub ufos_left: [u32; 3],
+    pub alien_base_destroyed: bool,
+    // Success tokens added to the alien base this round
+    #[serde(default)]
+    pub alien_base_damage: u32,
+    // Older saves and logs didn't record spending, so their rounds count as spending nothing
+    #[serde(default)]
+    pub spending: RoundSpending,
+    // Interceptors shot down on each continent during UFO defence, in the same order as
+    //   ALL_CONTINENTS
+    #[serde(default)]
+    pub interceptors_lost: [u32; 3],
+    // Success tokens gained during the research task, and whether the alien threat destroyed
+    //   the tech being researched
+    #[serde(default)]
+    pub research_successes: u32,
+    #[serde(default)]
+    pub research_destroyed: bool,
+    // Owned techs used during the resolution phase
+    #[serde(default)]
+    pub techs_used: Vec<Tech>,
+}
+
+// Board states used to record a single total. The split between continents wasn't kept, so
+//   the total is spread over the continents in order to keep older saves and logs loading
+fn deserialize_ufos_left<'de, D>(deserializer: D) -> Result<[u32; 3], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum UfosLeft {
+        PerContinent([u32; 3]),
+        Total(u32),
+    }
+
+    Ok(match UfosLeft::deserialize(deserializer)? {
+        UfosLeft::PerContinent(ufos_left) => ufos_left,
+        UfosLeft::Total(mut total) => {
+            let mut ufos_left = [0; 3];
+            for ufos in ufos_left.iter_mut() {
+                *ufos = total.min(MAX_UFOS_PER_CONTINENT);
+                total -= *ufos;
+            }
+            ufos_left
+        }
+    })
+}
+
+impl BoardState {
+    pub fn ufos_left_on(&self, continent: &Continent) -> u32 {
+        self.ufos_left[continent.index()]
+    }
+
+    pub fn total_ufos_left(&self) -> u32 {
+        self.ufos_left.iter().sum()
+    }
+
+    // Continents with any UFOs left each raise the panic track one space
+    pub fn continents_increasing_panic(&self) -> Vec<Continent> {
+        ALL_CONTINENTS
+            .iter()
+            .filter(|continent| self.ufos_left_on(continent) > 0)
+            .cloned()
+            .collect()
+    }
+
+    // Continents cleared of UFOs grant their bonus
+    pub fn earned_continent_bonuses(&self) -> Vec<Continent> {
+        ALL_CONTINENTS
+            .iter()
+            .filter(|continent| self.ufos_left_on(continent) == 0)
+            .cloned()
+            .collect()
+    }
+
+    pub fn game_result(&self) -> Option<GameResult> {
+        match (self.alien_base_destroyed, self.panic.is_alien_space()) {
+            (true, false) => Some(GameResult::Victory),
+            (true, true) => Some(GameResult::PyrrhicVictory),
+            (false, true) => Some(GameResult::Defeat),
+            (false, false) => None,
+        }
+    }
+}
+
+// The prompts generated for the round being played, kept until the round goes in the log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RoundInProgress {
+    prompts: Vec<TimedPhasePrompt>,
+    started_at_ms: u64,
+    #[serde(default)]
+    timer_expired: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Game {
+    state: GameState,
+    result: Option<GameResult>,
+    log: GameLog,
+    round_in_progress: Option<RoundInProgress>,
+}
+
+impl Game {
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Self {
+            state: GameState {
+                seed,
+                difficulty,
+                round: 1,
+                alien_base: None,
+                panic: PanicTrack::default(),
+                ufos_left: 0,
+                forces: Forces::new(),
+                tech_deck: TechDeck::shuffled(&mut round_rng(seed, 0)),
+                timer_expiry_rule: TimerExpiryRule::default(),
+                ironman: false,
+            },
+            result: None,
+            log: GameLog::new(seed, difficulty),
+            round_in_progress: None,
+        }
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    // Chosen while setting up the game, along with the difficulty
+    pub fn set_timer_expiry_rule(&mut self, timer_expiry_rule: TimerExpiryRule) {
+        self.state.timer_expiry_rule = timer_expiry_rule;
+        self.log.timer_expiry_rule = timer_expiry_rule;
+    }
+
+    // Also chosen while setting up the game, and can't be changed once it has started
+    pub fn set_ironman(&mut self, ironman: bool) {
+        self.state.ironman = ironman;
+        self.log.ironman = ironman;
+    }
+
+    pub fn result(&self) -> Option<&GameResult> {
+        self.result.as_ref()
+    }
+
+    pub fn log(&self) -> &GameLog {
+        &self.log
+    }
+
+    // Worked out from the log rather than stored, so undoing a round can't leave the funds out
+    //   of step. Includes the income for the round being played
+    pub fn funds(&self) -> FundsLedger {
+        let mut funds = FundsLedger::new();
+        for entry in self.log.rounds() {
+            funds.take_income(entry.round, &entry.prompts);
+            funds.resolve_round(entry.round, &entry.board_state);
+        }
+        if let Some(ref round_in_progress) = self.round_in_progress {
+            funds.take_income(self.state.round, &round_in_progress.prompts);
+        }
+        funds
+    }
+
+    // Generates the current round's timed phase prompts. The same round always gives the same
+    //   prompts, so this is safe to call again e.g. after reloading
+    pub fn start_round(&mut self, timestamp_ms: u64) -> Vec<TimedPhasePrompt> {
+        let state = &self.state;
+        let prompts = generate_timed_phase_prompts(
+            state.round,
+            &state.panic,
+            state.ufos_left,
+            state.alien_base.is_none()
+                && state.round == state.difficulty.alien_base_discovery_round(),
+            &state.difficulty,
+            &EscalationSchedule::default(),
+            &mut round_rng(state.seed, state.round),
+        );
+        let timer_expired = self
+            .round_in_progress
+            .as_ref()
+            .is_some_and(|round| round.timer_expired);
+        self.round_in_progress = Some(RoundInProgress {
+            prompts: prompts.clone(),
+            started_at_ms: timestamp_ms,
+            timer_expired,
+        });
+        prompts
+    }
+
+    // Records that the timed phase ran out of time, applying the penalty from the expiry rule.
+    //   Only the first expiry in a round counts
+    pub fn expire_timer(&mut self) -> bool {
+        match self.round_in_progress {
+            Some(ref mut round) if !round.timer_expired => round.timer_expired = true,
+            _ => return false,
+        }
+        if let TimerExpiryRule::IncreasePanic = self.state.timer_expiry_rule {
+            self.state.panic.increase(TIMER_EXPIRY_PANIC);
+        }
+        true
+    }
+
+    pub fn timer_expired(&self) -> bool {
+        self.round_in_progress
+            .as_ref()
+            .is_some_and(|round| round.timer_expired)
+    }
+
+    // Records the interceptors deployed and satellites used during the timed phase
+    pub fn deploy_forces(&mut self, forces: Forces) {
+        self.state.forces = forces;
+    }
+
+    // Records the research chosen during the timed phase
+    pub fn choose_research(&mut self, tech_deck: TechDeck) {
+        self.state.tech_deck = tech_deck;
+    }
+
+    pub fn complete_timed_phase(&mut self, prompts: &[TimedPhasePrompt]) {
+        for prompt in prompts.iter() {
+            if let TimedPhasePrompt::AlienBaseDiscovered(continent) = prompt {
+                self.state.alien_base = Some(AlienBase::new(continent.clone()));
+            }
+        }
+    }
+
+    // Replacement forces are limited by the funds left once the round is paid for, and satellites
+    //   by the room left for them. Limited here, before the round is logged, so the funds and the
+    //   forces agree on what was bought
+    fn limit_purchases(&self, board_state: &mut BoardState) {
+        let mut before_purchases = board_state.clone();
+        before_purchases.spending.interceptors_purchased = 0;
+        before_purchases.spending.satellites_purchased = 0;
+        let mut funds = self.funds();
+        funds.resolve_round(self.state.round, &before_purchases);
+        let mut forces = self.state.forces.clone();
+        forces.resolve_round(&before_purchases);
+
+        let spending = &mut board_state.spending;
+        spending.interceptors_purchased = spending
+            .interceptors_purchased
+            .min(funds.balance() / INTERCEPTOR_PRICE);
+        let balance = funds.balance() - spending.interceptors_purchased * INTERCEPTOR_PRICE;
+        spending.satellites_purchased = spending
+            .satellites_purchased
+            .min(forces.satellite_space())
+            .min(balance / SATELLITE_PRICE);
+    }
+
+    // Ends the round with the board state reported by the player, moving on to the next round
+    //   unless the game is over
+    pub fn submit_board_state(
+        &mut self,
+        mut board_state: BoardState,
+        timestamp_ms: u64,
+    ) -> Option<GameResult> {
+        self.limit_purchases(&mut board_state);
+        self.result = board_state.game_result();
+        let round_in_progress = self.round_in_progress.take();
+        self.log.result = self.result.clone();
+        self.log.push(RoundLogEntry {
+            round: self.state.round,
+            prompts: round_in_progress
+                .as_ref()
+                .map(|round| round.prompts.clone())
+                .unwrap_or_default(),
+            board_state: board_state.clone(),
+            alien_base_discovered: self.state.alien_base.is_some(),
+            timer_expired: round_in_progress
+                .as_ref()
+                .is_some_and(|round| round.timer_expired),
+            started_at_ms: round_in_progress
+                .as_ref()
+                .map(|round| round.started_at_ms)
+                .unwrap_or(timestamp_ms),
+            completed_at_ms: timestamp_ms,
+        });
+        if self.result.is_none() {
+            self.state.panic = board_state.panic;
+            self.state.ufos_left = board_state.total_ufos_left();
+            self.state.forces.resolve_round(&board_state);
+            self.state.tech_deck.resolve_round(&board_state);
+            self.state.tech_deck.refresh();
+            if let Some(alien_base) = self.state.alien_base.as_mut() {
+                alien_base.add_damage(board_state.alien_base_damage);
+            }
+            self.state.round += 1;
+        }
+        self.result.clone()
+    }
+
+    // Allows the board state to be submitted again if the player made a mistake ending the game,
+    //   unless it's an ironman game
+    pub fn undo_game_result(&mut self) {
+        if self.state.ironman {
+            return;
+        }
+        self.result = None;
+        if let Some(entry) = self.log.retract_last() {
+            self.round_in_progress = Some(RoundInProgress {
+                prompts: entry.prompts,
+                started_at_ms: entry.started_at_ms,
+                timer_expired: entry.timer_expired,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        roll_missing_ufo_dice, PanicLevel, PanicLevelInput, UfoTracker, MAX_SATELLITES,
+        ORANGE_PANIC_SPACE, STARTING_FUNDS, STARTING_RESERVES, STARTING_SATELLITES,
+    };
+    use test_case::test_case;
+
+    fn board_state(panic_level: PanicLevelInput, alien_base_destroyed: bool) -> BoardState {
+        BoardState {
+            panic: PanicTrack::at_level(&panic_level),
+            ufos_left: [1, 0, 3],
+            alien_base_destroyed,
+            ..BoardState::default()
+        }
+    }
+
+    #[test_case([0, 0, 0], vec![], ALL_CONTINENTS.to_vec())]
+    #[test_case(
+        [2, 0, 1],
+        vec![Continent::America, Continent::Eurasia],
+        vec![Continent::Africa]
+    )]
+    #[test_case([1, 6, 3], ALL_CONTINENTS.to_vec(), vec![])]
+    fn continent_ufos_test(
+        ufos_left: [u32; 3],
+        expected_panic: Vec<Continent>,
+        expected_bonuses: Vec<Continent>,
+    ) {
+        let board_state = BoardState {
+            ufos_left,
+            ..BoardState::default()
+        };
+        assert_eq!(expected_panic, board_state.continents_increasing_panic());
+        assert_eq!(expected_bonuses, board_state.earned_continent_bonuses());
+    }
+
+    #[test_case("[1,0,3]", [1, 0, 3])]
+    #[test_case("4", [4, 0, 0])]
+    #[test_case("15", [6, 6, 3])]
+    fn deserialize_ufos_left_test(ufos_left: &str, expected: [u32; 3]) {
+        let json = format!(
+            r#"{{"panic_level":"AlienSpace","ufos_left":{},"alien_base_destroyed":false}}"#,
+            ufos_left
+        );
+        let board_state: BoardState = serde_json::from_str(&json).unwrap();
+        assert_eq!(expected, board_state.ufos_left);
+    }
+
+    #[test_case(
+        PanicLevelInput::PanicLevel(PanicLevel::Red),
+        true,
+        Some(GameResult::Victory)
+    )]
+    #[test_case(PanicLevelInput::AlienSpace, true, Some(GameResult::PyrrhicVictory))]
+    #[test_case(PanicLevelInput::AlienSpace, false, Some(GameResult::Defeat))]
+    #[test_case(PanicLevelInput::PanicLevel(PanicLevel::Red), false, None)]
+    fn game_result_test(
+        panic_level: PanicLevelInput,
+        alien_base_destroyed: bool,
+        expected: Option<GameResult>,
+    ) {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        let result = game.submit_board_state(board_state(panic_level, alien_base_destroyed), 0);
+        assert_eq!(expected, result);
+        assert_eq!(expected.as_ref(), game.result());
+    }
+
+    #[test]
+    fn next_round_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        game.submit_board_state(
+            board_state(PanicLevelInput::PanicLevel(PanicLevel::Orange), false),
+            0,
+        );
+        assert_eq!(2, game.state().round);
+        assert_eq!(PanicTrack::new(ORANGE_PANIC_SPACE), game.state().panic);
+        assert_eq!(4, game.state().ufos_left);
+    }
+
+    #[test]
+    fn game_over_does_not_advance_round_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        game.submit_board_state(board_state(PanicLevelInput::AlienSpace, false), 0);
+        assert_eq!(1, game.state().round);
+
+        game.undo_game_result();
+        assert_eq!(None, game.result());
+        game.submit_board_state(
+            board_state(PanicLevelInput::PanicLevel(PanicLevel::Red), false),
+            0,
+        );
+        assert_eq!(2, game.state().round);
+    }
+
+    #[test]
+    fn ironman_game_over_is_final_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        game.set_ironman(true);
+        game.submit_board_state(board_state(PanicLevelInput::AlienSpace, false), 0);
+
+        game.undo_game_result();
+        assert_eq!(Some(&GameResult::Defeat), game.result());
+        assert_eq!(1, game.log().rounds().len());
+        assert!(game.log().ironman);
+    }
+
+    #[test]
+    fn alien_base_discovered_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        while game.state().round < Difficulty::Normal.alien_base_discovery_round() {
+            let prompts = game.start_round(0);
+            game.complete_timed_phase(&prompts);
+            assert!(game.state().alien_base.is_none());
+            game.submit_board_state(
+                board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false),
+                0,
+            );
+        }
+        let prompts = game.start_round(0);
+        assert_eq!(prompts, game.start_round(0));
+        game.complete_timed_phase(&prompts);
+        let continent = prompts
+            .iter()
+            .find_map(|prompt| match prompt {
+                TimedPhasePrompt::AlienBaseDiscovered(continent) => Some(continent),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            Some(continent),
+            game.state()
+                .alien_base
+                .as_ref()
+                .map(|alien_base| alien_base.continent())
+        );
+
+        let mut board_state = board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false);
+        board_state.alien_base_damage = 2;
+        game.submit_board_state(board_state, 0);
+        assert_eq!(
+            Some(2),
+            game.state()
+                .alien_base
+                .as_ref()
+                .map(|alien_base| alien_base.damage())
+        );
+    }
+
+    #[test_case(TimerExpiryRule::Suggestion, 0)]
+    #[test_case(TimerExpiryRule::EndPhase, 0)]
+    #[test_case(TimerExpiryRule::IncreasePanic, TIMER_EXPIRY_PANIC)]
+    fn expire_timer_test(timer_expiry_rule: TimerExpiryRule, expected_panic: u32) {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        game.set_timer_expiry_rule(timer_expiry_rule);
+        assert!(!game.expire_timer());
+        let prompts = game.start_round(0);
+        assert!(game.expire_timer());
+        assert!(!game.expire_timer());
+        game.start_round(0);
+        assert!(game.timer_expired());
+        game.complete_timed_phase(&prompts);
+        assert_eq!(expected_panic, game.state().panic.space());
+
+        game.submit_board_state(
+            board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false),
+            0,
+        );
+        assert!(!game.timer_expired());
+        assert!(game.log().rounds()[0].timer_expired);
+        assert_eq!(timer_expiry_rule, game.log().timer_expiry_rule);
+    }
+
+    #[test]
+    fn end_phase_resolves_unreached_prompts_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        game.set_timer_expiry_rule(TimerExpiryRule::EndPhase);
+        while game.state().round < Difficulty::Normal.alien_base_discovery_round() {
+            game.start_round(0);
+            game.submit_board_state(
+                board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false),
+                0,
+            );
+        }
+        let funds_before = game.funds().balance();
+        let prompts = game.start_round(0);
+        // Time runs out before the first prompt is done
+        assert!(game.expire_timer());
+        let mut ufo_rolls = vec![None; prompts.len()];
+        roll_missing_ufo_dice(&prompts, &mut ufo_rolls, &mut round_rng(1234, 0));
+        game.complete_timed_phase(&prompts);
+
+        let income = prompts
+            .iter()
+            .filter_map(|prompt| match prompt {
+                TimedPhasePrompt::TakeIncome(income) => Some(*income),
+                _ => None,
+            })
+            .sum::<i32>();
+        assert_eq!(funds_before as i32 + income, game.funds().balance() as i32);
+        assert!(game.state().alien_base.is_some());
+        // Every continent's UFOs are known, including from the dice rolled for the player
+        assert!(UfoTracker::from_prompts(&prompts, &ufo_rolls)
+            .total()
+            .is_some());
+    }
+
+    #[test]
+    fn game_log_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        let prompts = game.start_round(1000);
+        game.complete_timed_phase(&prompts);
+        game.submit_board_state(
+            board_state(PanicLevelInput::PanicLevel(PanicLevel::Orange), false),
+            2000,
+        );
+        game.start_round(3000);
+        game.submit_board_state(board_state(PanicLevelInput::AlienSpace, false), 4000);
+        game.undo_game_result();
+        game.submit_board_state(board_state(PanicLevelInput::AlienSpace, true), 5000);
+
+        let log = game.log();
+        assert_eq!(Some(GameResult::PyrrhicVictory), log.result);
+        assert_eq!(2, log.rounds().len());
+        assert_eq!(
+            RoundLogEntry {
+                round: 1,
+                prompts,
+                board_state: board_state(PanicLevelInput::PanicLevel(PanicLevel::Orange), false),
+                alien_base_discovered: false,
+                timer_expired: false,
+                started_at_ms: 1000,
+                completed_at_ms: 2000,
+            },
+            log.rounds()[0]
+        );
+        assert_eq!(2, log.rounds()[1].round);
+        assert_eq!(3000, log.rounds()[1].started_at_ms);
+        assert_eq!(5000, log.rounds()[1].completed_at_ms);
+        assert_eq!(log, &GameLog::from_json(&log.to_json().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn forces_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        let prompts = game.start_round(0);
+        let mut forces = game.state().forces.clone();
+        for _ in 0..3 {
+            forces.deploy(&Continent::Africa);
+        }
+        forces.use_satellite();
+        game.deploy_forces(forces);
+        game.complete_timed_phase(&prompts);
+
+        let mut round = board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false);
+        round.interceptors_lost = [0, 2, 0];
+        game.submit_board_state(round, 0);
+        let forces = &game.state().forces;
+        assert_eq!(STARTING_RESERVES - 2, forces.reserves());
+        assert_eq!(0, forces.total_deployed());
+        assert_eq!(STARTING_SATELLITES - 1, forces.satellites());
+    }
+
+    #[test]
+    fn research_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        let prompts = game.start_round(0);
+        let mut tech_deck = game.state().tech_deck.clone();
+        let tech = tech_deck.draw()[0].clone();
+        tech_deck.choose(Some(&tech));
+        game.choose_research(tech_deck);
+        game.complete_timed_phase(&prompts);
+
+        let mut round = board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false);
+        round.research_successes = tech.cost();
+        game.submit_board_state(round, 0);
+        assert_eq!(std::slice::from_ref(&tech), game.state().tech_deck.owned());
+        assert!(game.state().tech_deck.researching().is_none());
+
+        let mut tech_deck = game.state().tech_deck.clone();
+        tech_deck.use_tech(&tech);
+        game.choose_research(tech_deck);
+        game.submit_board_state(
+            board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false),
+            0,
+        );
+        assert!(!game.state().tech_deck.is_exhausted(&tech));
+    }
+
+    #[test]
+    fn funds_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        let prompts = game.start_round(0);
+        let income = prompts
+            .iter()
+            .find_map(|prompt| match prompt {
+                TimedPhasePrompt::TakeIncome(income) => Some(*income as u32),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(STARTING_FUNDS + income, game.funds().balance());
+
+        let mut first_round = board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false);
+        first_round.ufos_left = [1, 1, 1];
+        first_round.spending.interceptors_deployed = 3;
+        game.submit_board_state(first_round, 0);
+        assert_eq!(STARTING_FUNDS + income - 3, game.funds().balance());
+
+        // Undoing the round that ended the game also undoes its spending
+        let mut final_round = board_state(PanicLevelInput::AlienSpace, false);
+        final_round.ufos_left = [1, 1, 1];
+        final_round.spending.research_budget = 2;
+        game.submit_board_state(final_round, 0);
+        assert_eq!(STARTING_FUNDS + income - 5, game.funds().balance());
+        game.undo_game_result();
+        assert_eq!(STARTING_FUNDS + income - 3, game.funds().balance());
+    }
+
+    #[test]
+    fn purchases_limited_by_funds_and_satellite_space_test() {
+        let mut game = Game::new(1234, Difficulty::Normal);
+        game.start_round(0);
+        let balance = game.funds().balance();
+        let mut round = board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false);
+        // Africa's bonus isn't earned, and America's doesn't add a satellite
+        round.ufos_left = [1, 1, 1];
+        round.spending.interceptors_purchased = balance - SATELLITE_PRICE;
+        round.spending.satellites_purchased = 3;
+        game.submit_board_state(round, 0);
+
+        // Only one satellite fits, and there's no money left for another anyway
+        let spending = &game.log().rounds()[0].board_state.spending;
+        assert_eq!(balance - SATELLITE_PRICE, spending.interceptors_purchased);
+        assert_eq!(1, spending.satellites_purchased);
+        assert_eq!(0, game.funds().balance());
+        let forces = &game.state().forces;
+        assert_eq!(
+            STARTING_RESERVES + balance - SATELLITE_PRICE,
+            forces.reserves()
+        );
+        assert_eq!(MAX_SATELLITES, forces.satellites());
+
+        game.start_round(0);
+        let balance = game.funds().balance();
+        let mut round = board_state(PanicLevelInput::PanicLevel(PanicLevel::Yellow), false);
+        round.ufos_left = [1, 1, 1];
+        round.spending.interceptors_purchased = balance + 5;
+        game.submit_board_state(round, 0);
+        assert_eq!(
+            balance,
+            game.log().rounds()[1]
+                .board_state
+                .spending
+                .interceptors_purchased
+        );
+        assert_eq!(0, game.funds().balance());
+    }
+}
@@ -1,10 +1,42 @@
+mod alien_base;
+mod dice;
+mod difficulty;
+mod escalation;
+mod forces;
+mod funds;
+mod game;
+mod log;
+mod panic;
+mod tech;
+mod timer_expiry;
+mod ufo_tracker;
+
 use std::fmt::Display;
 
+pub use alien_base::{AlienBase, ALIEN_BASE_HEALTH};
+pub use dice::{
+    Task, TaskRoll, TaskRoller, ALIEN_DIE_SIDES, MAX_ALIEN_THREAT, MIN_ALIEN_THREAT,
+    SUCCESS_DIE_SIDES, SUCCESS_DIE_THRESHOLD,
+};
+pub use difficulty::{Difficulty, ALL_DIFFICULTIES};
+pub use escalation::{EscalationEvent, EscalationSchedule, EscalationStep};
+pub use forces::{Forces, MAX_SATELLITES, STARTING_RESERVES, STARTING_SATELLITES};
+pub use funds::{
+    Audit, FundsEntry, FundsLedger, FundsTransaction, RoundSpending, AFRICA_BONUS_FUNDS,
+    ELERIUM_GENERATOR_FUNDS, INTERCEPTOR_PRICE, SATELLITE_PRICE, STARTING_FUNDS,
+};
+pub use game::{BoardState, Game, GameState};
+pub use log::{GameLog, RoundLogEntry};
+pub use panic::{PanicTrack, ALIEN_PANIC_SPACE, ORANGE_PANIC_SPACE, RED_PANIC_SPACE};
 use rand::{
     distributions::{Uniform, WeightedIndex},
     prelude::*,
+    rngs::StdRng,
 };
 use serde::{Deserialize, Serialize};
+pub use tech::{Research, Tech, TechDeck, ALL_TECHS, RESEARCH_CHOICES};
+pub use timer_expiry::{TimerExpiryRule, ALL_TIMER_EXPIRY_RULES, TIMER_EXPIRY_PANIC};
+pub use ufo_tracker::{roll_missing_ufo_dice, roll_ufo_die, UfoTracker, MAX_UFOS_PER_CONTINENT};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PanicLevel {
@@ -13,6 +45,49 @@ pub enum PanicLevel {
     Red,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PanicLevelInput {
+    PanicLevel(PanicLevel),
+    AlienSpace,
+}
+
+impl Display for PanicLevelInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PanicLevelInput::PanicLevel(panic_level) => write!(f, "{:?}", panic_level),
+            PanicLevelInput::AlienSpace => write!(f, "Alien Space"),
+        }
+    }
+}
+
+impl From<PanicLevelInput> for String {
+    fn from(panic_level_input: PanicLevelInput) -> Self {
+        match panic_level_input {
+            PanicLevelInput::PanicLevel(PanicLevel::Yellow) => "yellow",
+            PanicLevelInput::PanicLevel(PanicLevel::Orange) => "orange",
+            PanicLevelInput::PanicLevel(PanicLevel::Red) => "red",
+            PanicLevelInput::AlienSpace => "alien",
+        }
+        .to_owned()
+    }
+}
+
+pub struct InvalidPanicLevelString;
+
+impl TryFrom<&str> for PanicLevelInput {
+    type Error = InvalidPanicLevelString;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "yellow" => Ok(PanicLevelInput::PanicLevel(PanicLevel::Yellow)),
+            "orange" => Ok(PanicLevelInput::PanicLevel(PanicLevel::Orange)),
+            "red" => Ok(PanicLevelInput::PanicLevel(PanicLevel::Red)),
+            "alien" => Ok(PanicLevelInput::AlienSpace),
+            _ => Err(InvalidPanicLevelString),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Continent {
     America,
@@ -29,6 +104,15 @@ impl Continent {
         }
         .to_owned()
     }
+
+    // Position in ALL_CONTINENTS, used to index per-continent arrays
+    pub fn index(&self) -> usize {
+        match self {
+            Continent::America => 0,
+            Continent::Africa => 1,
+            Continent::Eurasia => 2,
+        }
+    }
 }
 
 impl Display for &Continent {
@@ -51,6 +135,16 @@ where
     ALL_CONTINENTS.choose(rng).unwrap().clone()
 }
 
+pub fn random_seed() -> u64 {
+    thread_rng().gen()
+}
+
+// Each round gets its own RNG derived from the game seed, so regenerating a round's prompts
+//   (e.g. after reloading) gives the same result and a whole game can be replayed from its seed
+pub fn round_rng(seed: u64, round: u32) -> StdRng {
+    StdRng::seed_from_u64(seed ^ (round as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TimedPhasePrompt {
     TakeIncome(i32),
@@ -118,23 +212,21 @@ impl TimedPhasePrompt {
 
 pub fn generate_timed_phase_prompts<R>(
     round: u32,
-    panic: &PanicLevel,
+    panic: &PanicTrack,
     leftover_ufos: u32,
     discover_alien_base: bool,
+    difficulty: &Difficulty,
+    schedule: &EscalationSchedule,
     rng: &mut R,
 ) -> Vec<TimedPhasePrompt>
 where
     R: Rng,
 {
     let random_income_amounts = [-1, 0, 1];
-    let random_income_weights = [0.30, 0.50, 0.20];
+    let random_income_weights = difficulty.income_adjustment_weights();
     let random_income_dist = WeightedIndex::new(&random_income_weights).unwrap();
     let random_income_adjustment = random_income_amounts[random_income_dist.sample(rng)];
-    let income = match panic {
-        PanicLevel::Yellow => 6,
-        PanicLevel::Orange => 5,
-        PanicLevel::Red => 4,
-    } + random_income_adjustment;
+    let income = difficulty.base_income(panic) + random_income_adjustment;
     let income_prompt = TimedPhasePrompt::TakeIncome(income);
 
     let mut round_continents = Vec::from(ALL_CONTINENTS.clone());
@@ -150,52 +242,22 @@ where
         .map(|continent| TimedPhasePrompt::RollUFOLocation(continent.clone()))
         .collect();
 
-    let mut bonus_ufo_prompts = Vec::new();
-    if round >= 2 {
-        bonus_ufo_prompts.push(TimedPhasePrompt::AddUFOsToLocation(
-            random_continent(rng),
-            2,
-        ));
-    }
-    if round >= 3 {
-        round_continents.shuffle(rng);
-        bonus_ufo_prompts.push(TimedPhasePrompt::SwapUFOLocations(
-            round_continents[0].clone(),
-            round_continents[1].clone(),
-        ));
-    }
-    if round >= 4 {
-        bonus_ufo_prompts.push(TimedPhasePrompt::AddUFOsToLocation(
-            random_continent(rng),
-            1,
-        ));
-    }
-    if round >= 5 {
-        bonus_ufo_prompts.push(TimedPhasePrompt::AddUFOsToLocation(
-            random_continent(rng),
-            1,
-        ));
-    }
-    if round >= 6 {
-        round_continents.shuffle(rng);
-        bonus_ufo_prompts.push(TimedPhasePrompt::SwapUFOLocations(
-            round_continents[0].clone(),
-            round_continents[1].clone(),
-        ));
-    }
-    if round >= 7 {
-        bonus_ufo_prompts.push(TimedPhasePrompt::AddUFOsToLocation(
-            random_continent(rng),
-            2,
-        ));
-    }
-    if round >= 8 {
-        round_continents.shuffle(rng);
-        bonus_ufo_prompts.push(TimedPhasePrompt::SwapUFOLocations(
-            round_continents[0].clone(),
-            round_continents[1].clone(),
-        ));
-    }
+    let mut bonus_ufo_prompts: Vec<TimedPhasePrompt> = schedule
+        .events_for_round(difficulty.escalation_round(round))
+        .into_iter()
+        .map(|event| match event {
+            EscalationEvent::AddUFOs(amount) => {
+                TimedPhasePrompt::AddUFOsToLocation(random_continent(rng), amount)
+            }
+            EscalationEvent::SwapUFOs => {
+                round_continents.shuffle(rng);
+                TimedPhasePrompt::SwapUFOLocations(
+                    round_continents[0].clone(),
+                    round_continents[1].clone(),
+                )
+            }
+        })
+        .collect();
 
     if discover_alien_base {
         bonus_ufo_prompts.push(TimedPhasePrompt::AlienBaseDiscovered(random_continent(rng)));
@@ -214,16 +276,16 @@ where
     prompts.push(TimedPhasePrompt::ChooseResearch);
     prompts.push(TimedPhasePrompt::SetResearchBudget);
 
-    let num_shifts = match leftover_ufos {
-        n if n < 1 => 0,
-        n if n < 2 => 3,
+    shuffle_prompts(&mut prompts, difficulty.num_shifts(leftover_ufos), rng);
+    prompts
+}
 
-        n if n < 4 => 5,
-        n if n < 6 => 7,
-        n if n < 9 => 9,
-        n if n < 13 => 12,
-        _ => 15,
-    };
+// Moves randomly chosen prompts to random positions, keeping every prompt after the prompts it
+//   must come after
+fn shuffle_prompts<R>(prompts: &mut Vec<TimedPhasePrompt>, num_shifts: u32, rng: &mut R)
+where
+    R: Rng,
+{
     for _ in 0..num_shifts {
         let from_pos = Uniform::new(0, prompts.len()).sample(rng);
         let removed_prompt = prompts.remove(from_pos);
@@ -241,13 +303,47 @@ where
             .position(|other| removed_prompt.must_come_after(other))
             .map(|pos| prompts.len() - pos)
             .unwrap_or(0);
-        (min_insert_pos, max_insert_pos);
-        if min_insert_pos < (max_insert_pos + 1) {
-            let insert_pos = Uniform::new(min_insert_pos, max_insert_pos + 1).sample(rng);
-            prompts.insert(insert_pos, removed_prompt)
+        // If there's nowhere valid to move the prompt to, put it back where it came from
+        let insert_pos = if min_insert_pos <= max_insert_pos {
+            Uniform::new(min_insert_pos, max_insert_pos + 1).sample(rng)
+        } else {
+            from_pos
+        };
+        prompts.insert(insert_pos, removed_prompt);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderViolation {
+    pub prompt: TimedPhasePrompt,
+    pub must_come_after: TimedPhasePrompt,
+}
+
+impl Display for OrderViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} must come after {}",
+            self.prompt.title(),
+            self.must_come_after.title()
+        )
+    }
+}
+
+// Checks that no prompt comes before a prompt it must come after
+pub fn validate_prompt_order(prompts: &[TimedPhasePrompt]) -> Result<(), OrderViolation> {
+    for (pos, prompt) in prompts.iter().enumerate() {
+        if let Some(later_prompt) = prompts[pos + 1..]
+            .iter()
+            .find(|other| prompt.must_come_after(other))
+        {
+            return Err(OrderViolation {
+                prompt: prompt.clone(),
+                must_come_after: later_prompt.clone(),
+            });
         }
     }
-    return prompts;
+    Ok(())
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -255,6 +351,7 @@ pub enum ResolutionPhasePrompt {
     AuditSpending,
     ResolveResearch,
     ResolveUFODefence,
+    AssaultAlienBase,
     IncreasePanic,
     AskForBoardState,
     ResolveContinentBonuses,
@@ -268,8 +365,9 @@ impl ResolutionPhasePrompt {
             Self::AuditSpending,
             Self::ResolveResearch,
             Self::ResolveUFODefence,
-            Self::IncreasePanic,
+            Self::AssaultAlienBase,
             Self::AskForBoardState,
+            Self::IncreasePanic,
             Self::ResolveContinentBonuses,
             Self::CleanUp,
             Self::PurchaseReplacementForces,
@@ -281,6 +379,7 @@ impl ResolutionPhasePrompt {
             Self::AuditSpending => "Audit Spending",
             Self::ResolveResearch => "Resolve Research",
             Self::ResolveUFODefence => "Resolve UFO Defence",
+            Self::AssaultAlienBase => "Assault the Alien Base",
             Self::IncreasePanic => "Increase Panic",
             Self::AskForBoardState => "Update Board State",
             Self::ResolveContinentBonuses => "Gain Continent Bonuses",
@@ -290,6 +389,32 @@ impl ResolutionPhasePrompt {
         .to_owned()
     }
 }
+
+// Board state that each resolution phase prompt changes. Resolving every prompt in turn resolves
+//   the whole round
+pub trait ResolvesPrompts {
+    fn resolve_prompt(&mut self, prompt: &ResolutionPhasePrompt, board_state: &BoardState);
+
+    fn resolve_round(&mut self, board_state: &BoardState) {
+        for prompt in ResolutionPhasePrompt::all().iter() {
+            self.resolve_prompt(prompt, board_state);
+        }
+    }
+}
+
+// The resolution phase prompts for the round, leaving out any steps that can't apply to the
+//   current game
+pub fn generate_resolution_phase_prompts(state: &GameState) -> Vec<ResolutionPhasePrompt> {
+    ResolutionPhasePrompt::all()
+        .into_iter()
+        .filter(|prompt| match prompt {
+            ResolutionPhasePrompt::ResolveResearch => state.tech_deck.researching().is_some(),
+            ResolutionPhasePrompt::AssaultAlienBase => state.alien_base.is_some(),
+            _ => true,
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameResult {
     Victory,
@@ -310,6 +435,7 @@ impl Display for GameResult {
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::{prop, prop_assert_eq, proptest, ProptestConfig, Strategy};
     use test_case::test_case;
 
     #[test_case((TimedPhasePrompt::AssignInterceptors(Continent::Africa), TimedPhasePrompt::RollUFOLocation(Continent::Africa)),  true)]
@@ -322,4 +448,161 @@ mod test {
     fn succession_rules_test(prompts: (TimedPhasePrompt, TimedPhasePrompt), expected: bool) {
         assert_eq!(expected, prompts.0.must_come_after(&prompts.1));
     }
+
+    #[test_case(1)]
+    #[test_case(5)]
+    #[test_case(10)]
+    fn same_seed_same_prompts_test(round: u32) {
+        let generate = |seed| {
+            generate_timed_phase_prompts(
+                round,
+                &PanicTrack::new(ORANGE_PANIC_SPACE),
+                7,
+                round == 5,
+                &Difficulty::Normal,
+                &EscalationSchedule::default(),
+                &mut round_rng(seed, round),
+            )
+        };
+        assert_eq!(generate(1234), generate(1234));
+    }
+
+    #[test_case(false, false, 7)]
+    #[test_case(true, false, 8)]
+    #[test_case(false, true, 8)]
+    #[test_case(true, true, 9)]
+    fn resolution_phase_prompts_test(researching: bool, alien_base_discovered: bool, len: usize) {
+        let mut state = Game::new(1234, Difficulty::Normal).state().clone();
+        state.alien_base = alien_base_discovered.then(|| AlienBase::new(Continent::Eurasia));
+        if researching {
+            let tech = state.tech_deck.draw()[0].clone();
+            state.tech_deck.choose(Some(&tech));
+        }
+        let prompts = generate_resolution_phase_prompts(&state);
+        assert_eq!(len, prompts.len());
+        assert_eq!(
+            researching,
+            prompts.contains(&ResolutionPhasePrompt::ResolveResearch)
+        );
+        assert_eq!(
+            alien_base_discovered,
+            prompts.contains(&ResolutionPhasePrompt::AssaultAlienBase)
+        );
+    }
+
+    #[test]
+    fn rounds_use_different_rngs_test() {
+        let mut round_1 = round_rng(1234, 1);
+        let mut round_2 = round_rng(1234, 2);
+        assert_ne!(round_1.gen::<u64>(), round_2.gen::<u64>());
+    }
+
+    #[test_case(
+        vec![
+            TimedPhasePrompt::RollUFOLocation(Continent::Africa),
+            TimedPhasePrompt::AssignInterceptors(Continent::Africa),
+        ],
+        Ok(())
+    )]
+    #[test_case(
+        vec![
+            TimedPhasePrompt::AssignInterceptors(Continent::Africa),
+            TimedPhasePrompt::RollUFOLocation(Continent::Eurasia),
+        ],
+        Ok(())
+    )]
+    #[test_case(
+        vec![
+            TimedPhasePrompt::RollUFOLocation(Continent::Eurasia),
+            TimedPhasePrompt::SwapUFOLocations(Continent::Africa, Continent::Eurasia),
+            TimedPhasePrompt::RollUFOLocation(Continent::Africa),
+        ],
+        Err(OrderViolation {
+            prompt: TimedPhasePrompt::SwapUFOLocations(Continent::Africa, Continent::Eurasia),
+            must_come_after: TimedPhasePrompt::RollUFOLocation(Continent::Africa),
+        })
+    )]
+    fn validate_prompt_order_test(
+        prompts: Vec<TimedPhasePrompt>,
+        expected: Result<(), OrderViolation>,
+    ) {
+        assert_eq!(expected, validate_prompt_order(&prompts));
+    }
+
+    fn count(prompts: &[TimedPhasePrompt], prompt: &TimedPhasePrompt) -> usize {
+        prompts.iter().filter(|other| *other == prompt).count()
+    }
+
+    fn panic_strategy() -> impl Strategy<Value = PanicTrack> {
+        (0..ALIEN_PANIC_SPACE).prop_map(PanicTrack::new)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2000))]
+
+        #[test]
+        fn generated_prompts_are_ordered_test(
+            seed: u64,
+            round in 1..40u32,
+            panic in panic_strategy(),
+            leftover_ufos in 0..30u32,
+            discover_alien_base: bool,
+            difficulty in prop::sample::select(ALL_DIFFICULTIES.to_vec()),
+        ) {
+            let prompts = generate_timed_phase_prompts(
+                round,
+                &panic,
+                leftover_ufos,
+                discover_alien_base,
+                &difficulty,
+                &EscalationSchedule::default(),
+                &mut round_rng(seed, round),
+            );
+            prop_assert_eq!(Ok(()), validate_prompt_order(&prompts));
+            prop_assert_eq!(1, count(&prompts, &TimedPhasePrompt::ChooseResearch));
+            prop_assert_eq!(1, count(&prompts, &TimedPhasePrompt::SetResearchBudget));
+            for continent in ALL_CONTINENTS.iter() {
+                let assign_interceptors = TimedPhasePrompt::AssignInterceptors(continent.clone());
+                prop_assert_eq!(1, count(&prompts, &assign_interceptors));
+            }
+        }
+
+        #[test]
+        fn shuffle_keeps_every_prompt_test(
+            seed: u64,
+            round in 1..40u32,
+            num_shifts in 0..50u32,
+        ) {
+            let rng = &mut StdRng::seed_from_u64(seed);
+            let prompts = generate_timed_phase_prompts(
+                round,
+                &PanicTrack::default(),
+                0,
+                round == 5,
+                &Difficulty::Normal,
+                &EscalationSchedule::default(),
+                rng,
+            );
+            let mut shuffled = prompts.clone();
+            shuffle_prompts(&mut shuffled, num_shifts, rng);
+            prop_assert_eq!(Ok(()), validate_prompt_order(&shuffled));
+            prop_assert_eq!(prompts.len(), shuffled.len());
+            for prompt in prompts.iter() {
+                prop_assert_eq!(count(&prompts, prompt), count(&shuffled, prompt));
+            }
+        }
+
+        #[test]
+        fn shuffle_never_drops_misordered_prompts_test(seed: u64, num_shifts in 0..50u32) {
+            let mut prompts = vec![
+                TimedPhasePrompt::AssignInterceptors(Continent::Africa),
+                TimedPhasePrompt::AddUFOsToLocation(Continent::Africa, 1),
+                TimedPhasePrompt::RollUFOLocation(Continent::Africa),
+                TimedPhasePrompt::SwapUFOLocations(Continent::Africa, Continent::Eurasia),
+                TimedPhasePrompt::RollUFOLocation(Continent::Eurasia),
+            ];
+            shuffle_prompts(&mut prompts, num_shifts, &mut StdRng::seed_from_u64(seed));
+            prop_assert_eq!(5, prompts.len());
+        }
+    }
 }
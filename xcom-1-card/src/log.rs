@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardState, Difficulty, GameResult, TimedPhasePrompt, TimerExpiryRule};
+
+// Everything that happened in a single round. Timestamps are milliseconds since the unix epoch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundLogEntry {
+    pub round: u32,
+    pub prompts: Vec<TimedPhasePrompt>,
+    pub board_state: BoardState,
+    pub alien_base_discovered: bool,
+    // Whether the timed phase ran out of time
+    #[serde(default)]
+    pub timer_expired: bool,
+    pub started_at_ms: u64,
+    pub completed_at_ms: u64,
+}
+
+// A record of a whole game, with one entry per completed round
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameLog {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    // Older logs were from games where the timer was only ever a suggestion
+    #[serde(default)]
+    pub timer_expiry_rule: TimerExpiryRule,
+    // Whether the game was played without going back to earlier prompts
+    #[serde(default)]
+    pub ironman: bool,
+    pub result: Option<GameResult>,
+    rounds: Vec<RoundLogEntry>,
+}
+
+impl GameLog {
+    pub fn new(seed: u64, difficulty: Difficulty) -> Self {
+        Self {
+            seed,
+            difficulty,
+            timer_expiry_rule: TimerExpiryRule::default(),
+            ironman: false,
+            result: None,
+            rounds: Vec::new(),
+        }
+    }
+
+    pub fn rounds(&self) -> &[RoundLogEntry] {
+        &self.rounds
+    }
+
+    pub fn push(&mut self, entry: RoundLogEntry) {
+        self.rounds.push(entry);
+    }
+
+    // Only used when the player takes back the board state that ended the game
+    pub(crate) fn retract_last(&mut self) -> Option<RoundLogEntry> {
+        self.result = None;
+        self.rounds.pop()
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
@@ -1,7 +1,11 @@
-use rand::prelude::*;
-use std::io::{stdin, stdout, Write};
+use std::{
+    io::{stdin, stdout, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
 use xcom_1_card::{
-    generate_timed_phase_prompts, GameResult, PanicLevel, ResolutionPhasePrompt, TimedPhasePrompt,
+    generate_resolution_phase_prompts, random_seed, BoardState, Continent, Difficulty, Game,
+    GameResult, ResolutionPhasePrompt, ResolvesPrompts, Tech, TimedPhasePrompt, ALL_CONTINENTS,
+    MAX_UFOS_PER_CONTINENT,
 };
 
 fn prompt_console(input: &str) -> String {
@@ -12,122 +16,249 @@ fn prompt_console(input: &str) -> String {
     return buffer;
 }
 
-enum PanicLevelInput {
-    PanicLevel(PanicLevel),
-    AlienSpace,
-}
-
-fn get_panic_level_input() -> PanicLevelInput {
-    let mut panic_response: Option<PanicLevelInput> = None;
+fn get_additional_panic() -> u32 {
+    let mut panic_response: Option<u32> = None;
     while panic_response.is_none() {
-        let panic_level_input = prompt_console(
-            "What is the current Global Panic Level? [Y]ellow/[O]range/[R]ed/[A]lien space\n",
+        let panic_input = prompt_console(
+            "How many other spaces did the panic track move this round, besides the continents and unpaid spending above?\n",
         );
-        panic_response = match panic_level_input.to_ascii_lowercase().trim_end() {
-            "y" | "yellow" => Some(PanicLevelInput::PanicLevel(PanicLevel::Yellow)),
-            "o" | "orange" => Some(PanicLevelInput::PanicLevel(PanicLevel::Orange)),
-            "r" | "red" => Some(PanicLevelInput::PanicLevel(PanicLevel::Red)),
-            "a" | "aliens space" => Some(PanicLevelInput::AlienSpace),
-            _ => None,
+        panic_response = match panic_input.trim_end() {
+            "" => Some(0),
+            input => input.parse::<u32>().ok(),
         };
     }
     return panic_response.unwrap();
 }
 
-fn get_ufos_left() -> u32 {
+fn get_ufos_left(continent: &Continent) -> u32 {
     let mut ufos_response: Option<u32> = None;
     while ufos_response.is_none() {
-        let ufos_response_input = prompt_console("How many ufos were left on the world map?\n");
+        let ufos_response_input =
+            prompt_console(&format!("How many ufos were left in {}?\n", continent));
         ufos_response = match ufos_response_input.trim_end().parse::<u32>() {
-            Ok(n) => Some(n),
+            Ok(n) if n <= MAX_UFOS_PER_CONTINENT => Some(n),
             _ => None,
         };
     }
     return ufos_response.unwrap();
 }
 
-fn get_alien_base_destroyed() -> bool {
-    let mut alien_base_destroyed: Option<bool> = None;
-    while alien_base_destroyed.is_none() {
-        let alien_base_destroyed_input =
-            prompt_console("Was the alien base destroyed? [Y]es/[N]o\n");
-        alien_base_destroyed = match alien_base_destroyed_input.to_ascii_lowercase().trim_end() {
+fn get_number_input(question: &str) -> u32 {
+    let mut number_response: Option<u32> = None;
+    while number_response.is_none() {
+        let number_input = prompt_console(&format!("{}\n", question));
+        number_response = number_input.trim_end().parse::<u32>().ok();
+    }
+    number_response.unwrap()
+}
+
+fn get_yes_no_input(question: &str) -> bool {
+    let mut yes_no_response: Option<bool> = None;
+    while yes_no_response.is_none() {
+        let yes_no_input = prompt_console(&format!("{} [Y]es/[N]o\n", question));
+        yes_no_response = match yes_no_input.to_ascii_lowercase().trim_end() {
             "y" | "yes" => Some(true),
             "n" | "no" => Some(false),
             _ => None,
         };
     }
-    return alien_base_destroyed.unwrap();
+    yes_no_response.unwrap()
+}
+
+fn get_research_choice(drawn: &[Tech]) -> Option<Tech> {
+    let mut choice_response: Option<Option<Tech>> = None;
+    while choice_response.is_none() {
+        for (index, tech) in drawn.iter().enumerate() {
+            println!("[{}] {} (cost {})", index + 1, tech, tech.cost());
+        }
+        let choice_input = prompt_console(
+            "Which technology will you research? (leave blank to keep researching)\n",
+        );
+        choice_response = match choice_input.trim_end() {
+            "" => Some(None),
+            input => input
+                .parse::<usize>()
+                .ok()
+                .and_then(|choice| drawn.get(choice.wrapping_sub(1)))
+                .map(|tech| Some(tech.clone())),
+        };
+    }
+    choice_response.unwrap()
+}
+
+fn get_difficulty_input() -> Difficulty {
+    let mut difficulty_response: Option<Difficulty> = None;
+    while difficulty_response.is_none() {
+        let difficulty_input =
+            prompt_console("Choose a difficulty: [E]asy/[N]ormal/[H]ard/[I]mpossible\n");
+        difficulty_response = match difficulty_input.to_ascii_lowercase().trim_end() {
+            "e" | "easy" => Some(Difficulty::Easy),
+            "n" | "normal" | "" => Some(Difficulty::Normal),
+            "h" | "hard" => Some(Difficulty::Hard),
+            "i" | "impossible" => Some(Difficulty::Impossible),
+            _ => None,
+        };
+    }
+    return difficulty_response.unwrap();
 }
 
 fn main() {
-    let game_result = run_game();
+    // Pass a seed as the first argument to replay a previous game
+    let seed = match std::env::args().nth(1) {
+        Some(seed_arg) => seed_arg.parse::<u64>().expect("Seed must be a number"),
+        None => random_seed(),
+    };
+    println!("Seed: {}", seed);
+    let difficulty = get_difficulty_input();
+    let game_result = run_game(seed, difficulty);
     println!("{:?}", game_result);
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct GameState {
-    round: u32,
-    alien_base_discovered: bool,
-    panic_level: PanicLevel,
-    ufos_left: u32,
+fn timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
 }
 
-fn run_game() -> GameResult {
-    let rng = &mut thread_rng();
-
-    let mut game_state = GameState {
-        round: 1,
-        alien_base_discovered: false,
-        panic_level: PanicLevel::Yellow,
-        ufos_left: 0,
-    };
+fn run_game(seed: u64, difficulty: Difficulty) -> GameResult {
+    let mut game = Game::new(seed, difficulty);
     loop {
-        println!("Round {}", game_state.round);
+        println!("Round {}", game.state().round);
         prompt_console("Beginning Timed phase");
-        let timed_phase_prompts = generate_timed_phase_prompts(
-            game_state.round,
-            &game_state.panic_level,
-            game_state.ufos_left,
-            game_state.round == 5,
-            rng,
-        );
+        let timed_phase_prompts = game.start_round(timestamp_ms());
+        let mut forces = game.state().forces.clone();
+        let mut tech_deck = game.state().tech_deck.clone();
         for prompt in timed_phase_prompts.iter() {
             prompt_console(&format!("{:?}", prompt));
-            if let TimedPhasePrompt::AlienBaseDiscovered(_) = prompt {
-                game_state.alien_base_discovered = true;
+            match prompt {
+                TimedPhasePrompt::AssignInterceptors(continent) => {
+                    let interceptors = get_number_input(&format!(
+                        "How many interceptors did you assign to {}? ({} in reserves)",
+                        continent,
+                        forces.reserves()
+                    ));
+                    for _ in 0..interceptors {
+                        forces.deploy(continent);
+                    }
+                }
+                TimedPhasePrompt::ChooseResearch => {
+                    if let Some(research) = tech_deck.researching() {
+                        println!("Currently researching {}", research);
+                    }
+                    let choice = get_research_choice(tech_deck.draw());
+                    tech_deck.choose(choice.as_ref());
+                }
+                _ => {}
             }
         }
+        game.choose_research(tech_deck);
+        for _ in 0..get_number_input("How many satellites did you use?") {
+            forces.use_satellite();
+        }
+        game.deploy_forces(forces);
+        game.complete_timed_phase(&timed_phase_prompts);
         prompt_console("Ending Timed phase");
         prompt_console("Beginning Resolution phase");
-        let resolution_phase_prompts = ResolutionPhasePrompt::all();
+        let resolution_phase_prompts = generate_resolution_phase_prompts(game.state());
+        let mut board_state = BoardState {
+            panic: game.state().panic,
+            ..BoardState::default()
+        };
+        let mut audit = None;
         for prompt in resolution_phase_prompts.into_iter() {
             prompt_console(&format!("{:?}", prompt));
-            if prompt == ResolutionPhasePrompt::AskForBoardState {
-                let panic_level_input = get_panic_level_input();
-                let ufos_left = get_ufos_left();
-                let alien_base_destroyed = if game_state.alien_base_discovered {
-                    get_alien_base_destroyed()
-                } else {
-                    false
-                };
-                match (alien_base_destroyed, panic_level_input) {
-                    (true, PanicLevelInput::AlienSpace) => {
-                        return GameResult::PyrrhicVictory;
+            match prompt {
+                ResolutionPhasePrompt::AuditSpending => {
+                    let spending = &mut board_state.spending;
+                    spending.elerium_generator_used =
+                        get_yes_no_input("Did you use the Elerium Generator this round?");
+                    spending.interceptors_deployed = game.state().forces.total_deployed();
+                    println!("Interceptors deployed: {}", spending.interceptors_deployed);
+                    spending.research_budget = get_number_input("What is the research budget?");
+                    let round_audit = game.funds().audit(spending);
+                    println!(
+                        "Pay §{} of §{} owed ({} unpaid)",
+                        round_audit.paid, round_audit.cost, round_audit.unpaid
+                    );
+                    audit = Some(round_audit);
+                }
+                ResolutionPhasePrompt::ResolveResearch => {
+                    if let Some(research) = game.state().tech_deck.researching() {
+                        println!("Researching {}", research);
+                        board_state.research_successes =
+                            get_number_input("How many successes did you roll?");
+                        board_state.research_destroyed =
+                            get_yes_no_input("Did the alien threat destroy the technology?");
                     }
-                    (false, PanicLevelInput::AlienSpace) => {
-                        return GameResult::Defeat;
+                }
+                ResolutionPhasePrompt::ResolveUFODefence => {
+                    for continent in ALL_CONTINENTS.iter() {
+                        let deployed = game.state().forces.deployed_on(continent);
+                        if deployed > 0 {
+                            board_state.interceptors_lost[continent.index()] = get_number_input(
+                                &format!("How many interceptors were shot down in {}?", continent),
+                            )
+                            .min(deployed);
+                        }
                     }
-                    (true, _) => {
-                        return GameResult::Victory;
+                }
+                ResolutionPhasePrompt::AssaultAlienBase => {
+                    if let Some(alien_base) = game.state().alien_base.as_ref() {
+                        println!("{}", alien_base);
+                        board_state.alien_base_damage = get_number_input(
+                            "How many success tokens were added to the alien base?",
+                        )
+                        .min(alien_base.remaining_health());
+                        board_state.alien_base_destroyed =
+                            board_state.alien_base_damage >= alien_base.remaining_health();
                     }
-                    (false, PanicLevelInput::PanicLevel(panic_level)) => {
-                        game_state.panic_level = panic_level;
-                        game_state.ufos_left = ufos_left;
+                }
+                ResolutionPhasePrompt::AskForBoardState => {
+                    board_state.ufos_left =
+                        ALL_CONTINENTS.map(|continent| get_ufos_left(&continent));
+                }
+                ResolutionPhasePrompt::IncreasePanic => {
+                    let continents = board_state.continents_increasing_panic();
+                    println!("Increase panic for each of: {:?}", continents);
+                    let unpaid = audit.map(|audit| audit.unpaid).unwrap_or(0);
+                    println!("Increase panic for unpaid spending: {}", unpaid);
+                    board_state
+                        .panic
+                        .increase(continents.len() as u32 + unpaid + get_additional_panic());
+                    println!("Panic: {}", board_state.panic);
+                    if board_state.game_result().is_some() {
+                        break;
                     }
                 }
+                ResolutionPhasePrompt::ResolveContinentBonuses => {
+                    println!(
+                        "Gain the bonus for each of: {:?}",
+                        board_state.earned_continent_bonuses()
+                    );
+                }
+                ResolutionPhasePrompt::PurchaseReplacementForces => {
+                    let spending = &mut board_state.spending;
+                    spending.interceptors_purchased =
+                        get_number_input("How many interceptors did you buy?");
+                    let mut forces = game.state().forces.clone();
+                    forces.resolve_round(&board_state);
+                    board_state.spending.satellites_purchased =
+                        get_number_input("How many satellites did you buy?")
+                            .min(forces.satellite_space());
+                }
+                _ => {}
             }
         }
-        game_state.round += 1;
+        if let Some(game_result) = game.submit_board_state(board_state, timestamp_ms()) {
+            return game_result;
+        }
+        println!("Funds: §{}", game.funds().balance());
+        println!("{}", game.state().forces);
+        let tech_deck = &game.state().tech_deck;
+        if let Some(research) = tech_deck.researching() {
+            println!("Researching {}", research);
+        }
+        println!("Technology: {:?}", tech_deck.owned());
     }
 }
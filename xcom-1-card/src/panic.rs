@@ -0,0 +1,134 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{PanicLevel, PanicLevelInput};
+
+// The panic track has 2 yellow spaces, 3 orange spaces, 2 red spaces and then the alien space
+pub const ORANGE_PANIC_SPACE: u32 = 2;
+pub const RED_PANIC_SPACE: u32 = 5;
+pub const ALIEN_PANIC_SPACE: u32 = 7;
+
+// Position of the marker on the panic track, counting from the first yellow space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "PanicTrackRecord", into = "u32")]
+pub struct PanicTrack {
+    space: u32,
+}
+
+// Older saves and logs only recorded the level the marker was on, so those load as the first
+//   space of that level
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PanicTrackRecord {
+    Space(u32),
+    LevelInput(PanicLevelInput),
+    Level(PanicLevel),
+}
+
+impl From<PanicTrackRecord> for PanicTrack {
+    fn from(record: PanicTrackRecord) -> Self {
+        match record {
+            PanicTrackRecord::Space(space) => Self::new(space),
+            PanicTrackRecord::LevelInput(level) => Self::at_level(&level),
+            PanicTrackRecord::Level(level) => Self::at_level(&PanicLevelInput::PanicLevel(level)),
+        }
+    }
+}
+
+impl From<PanicTrack> for u32 {
+    fn from(panic: PanicTrack) -> Self {
+        panic.space
+    }
+}
+
+impl PanicTrack {
+    pub fn new(space: u32) -> Self {
+        Self {
+            space: space.min(ALIEN_PANIC_SPACE),
+        }
+    }
+
+    // The first space of the given level
+    pub fn at_level(level: &PanicLevelInput) -> Self {
+        Self::new(match level {
+            PanicLevelInput::PanicLevel(PanicLevel::Yellow) => 0,
+            PanicLevelInput::PanicLevel(PanicLevel::Orange) => ORANGE_PANIC_SPACE,
+            PanicLevelInput::PanicLevel(PanicLevel::Red) => RED_PANIC_SPACE,
+            PanicLevelInput::AlienSpace => ALIEN_PANIC_SPACE,
+        })
+    }
+
+    pub fn space(&self) -> u32 {
+        self.space
+    }
+
+    // The marker stops once it reaches the alien space
+    pub fn increased_by(&self, spaces: u32) -> Self {
+        Self::new(self.space.saturating_add(spaces))
+    }
+
+    pub fn increase(&mut self, spaces: u32) {
+        *self = self.increased_by(spaces);
+    }
+
+    pub fn level(&self) -> PanicLevelInput {
+        match self.space {
+            n if n >= ALIEN_PANIC_SPACE => PanicLevelInput::AlienSpace,
+            n if n >= RED_PANIC_SPACE => PanicLevelInput::PanicLevel(PanicLevel::Red),
+            n if n >= ORANGE_PANIC_SPACE => PanicLevelInput::PanicLevel(PanicLevel::Orange),
+            _ => PanicLevelInput::PanicLevel(PanicLevel::Yellow),
+        }
+    }
+
+    pub fn is_alien_space(&self) -> bool {
+        self.level() == PanicLevelInput::AlienSpace
+    }
+}
+
+impl Display for PanicTrack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}/{})", self.level(), self.space, ALIEN_PANIC_SPACE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, PanicLevelInput::PanicLevel(PanicLevel::Yellow))]
+    #[test_case(1, PanicLevelInput::PanicLevel(PanicLevel::Yellow))]
+    #[test_case(2, PanicLevelInput::PanicLevel(PanicLevel::Orange))]
+    #[test_case(4, PanicLevelInput::PanicLevel(PanicLevel::Orange))]
+    #[test_case(5, PanicLevelInput::PanicLevel(PanicLevel::Red))]
+    #[test_case(6, PanicLevelInput::PanicLevel(PanicLevel::Red))]
+    #[test_case(7, PanicLevelInput::AlienSpace)]
+    #[test_case(12, PanicLevelInput::AlienSpace)]
+    fn level_test(space: u32, expected: PanicLevelInput) {
+        assert_eq!(expected, PanicTrack::new(space).level());
+    }
+
+    #[test]
+    fn increase_stops_at_alien_space_test() {
+        let mut panic = PanicTrack::default();
+        panic.increase(3);
+        assert_eq!(3, panic.space());
+        panic.increase(10);
+        assert_eq!(ALIEN_PANIC_SPACE, panic.space());
+        assert!(panic.is_alien_space());
+    }
+
+    #[test_case("4", 4)]
+    #[test_case(r#"{"PanicLevel":"Orange"}"#, ORANGE_PANIC_SPACE)]
+    #[test_case(r#""AlienSpace""#, ALIEN_PANIC_SPACE)]
+    #[test_case(r#""Red""#, RED_PANIC_SPACE)]
+    fn deserialize_test(json: &str, expected_space: u32) {
+        let panic: PanicTrack = serde_json::from_str(json).unwrap();
+        assert_eq!(expected_space, panic.space());
+        assert_eq!(
+            expected_space.to_string(),
+            serde_json::to_string(&panic).unwrap()
+        );
+    }
+}
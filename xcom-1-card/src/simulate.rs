@@ -0,0 +1,590 @@
+use std::collections::BTreeMap;
+
+use rand::{prelude::*, rngs::StdRng};
+use xcom_1_card::{
+    generate_resolution_phase_prompts, random_seed, roll_ufo_die, AlienBase, BoardState, Continent,
+    Difficulty, Forces, FundsLedger, Game, GameResult, ResolutionPhasePrompt, ResolvesPrompts,
+    RoundSpending, Task, TaskRoll, TaskRoller, Tech, TechDeck, TimedPhasePrompt, UfoTracker,
+    ALL_CONTINENTS, ALL_DIFFICULTIES, ALL_TECHS, ELERIUM_GENERATOR_FUNDS, INTERCEPTOR_PRICE,
+    MAX_UFOS_PER_CONTINENT, SATELLITE_PRICE, STARTING_RESERVES, STARTING_SATELLITES,
+    SUCCESS_DIE_THRESHOLD,
+};
+
+// Games still going after this many rounds are counted as unfinished rather than looping forever
+const MAX_ROUNDS: u32 = 50;
+
+// The automated player's strategy
+const MAX_INTERCEPTORS_PER_CONTINENT: u32 = 6;
+const MAX_RESEARCH_BUDGET: u32 = 3;
+// Keeps re-attempting a task until the Alien Threat passes this level
+const RISK_TOLERANCE: u32 = 5;
+// What the player plans for on a continent whose UFO die hasn't been rolled yet
+const EXPECTED_UFOS: u32 = 4;
+// Money kept back when replacing forces, so next round's deployments can be paid for
+const FUNDS_KEPT_BACK: u32 = 3;
+// Research only comes out of savings beyond this, as interceptors do more to hold back panic
+const FUNDS_KEPT_FROM_RESEARCH: u32 = 8;
+
+// What's left once the round's spending so far is paid for
+fn spare_funds(funds: &FundsLedger, spending: &RoundSpending) -> u32 {
+    let available = if spending.elerium_generator_used {
+        funds.balance() + ELERIUM_GENERATOR_FUNDS
+    } else {
+        funds.balance()
+    };
+    available.saturating_sub(funds.audit(spending).cost)
+}
+
+// Whether the round's spending so far, plus the extra, can be paid without raising panic
+fn can_afford(funds: &FundsLedger, spending: &RoundSpending, extra: u32) -> bool {
+    extra <= spare_funds(funds, spending)
+}
+
+// A third more interceptors than targets usually clears a continent before losses mount up.
+//   Unrolled continents are planned for at the average roll
+fn interceptors_wanted(
+    continent: &Continent,
+    prompts: &[TimedPhasePrompt],
+    ufo_tracker: &UfoTracker,
+    alien_base: Option<&AlienBase>,
+) -> u32 {
+    let rolled_later = prompts
+        .iter()
+        .any(|prompt| prompt == &TimedPhasePrompt::RollUFOLocation(continent.clone()));
+    let ufos = match ufo_tracker.ufos(continent) {
+        Some(ufos) if !rolled_later => ufos,
+        _ => EXPECTED_UFOS,
+    };
+    let alien_base_health = alien_base
+        .filter(|alien_base| alien_base.continent() == continent)
+        .map(AlienBase::remaining_health)
+        .unwrap_or(0);
+    ((ufos + alien_base_health) * 4)
+        .div_ceil(3)
+        .min(MAX_INTERCEPTORS_PER_CONTINENT)
+}
+
+// Panic rises by the same amount however many UFOs are left on a continent, so the budget goes
+//   on clearing the cheapest continents completely rather than thinning out every one. Returns
+//   how many interceptors to send to the continent, out of those still to be assigned
+fn plan_interceptors(
+    continent: &Continent,
+    prompts: &[TimedPhasePrompt],
+    ufo_tracker: &UfoTracker,
+    alien_base: Option<&AlienBase>,
+    budget: u32,
+) -> u32 {
+    let mut unassigned = prompts
+        .iter()
+        .filter_map(|prompt| match prompt {
+            TimedPhasePrompt::AssignInterceptors(continent) => Some(continent),
+            _ => None,
+        })
+        .map(|continent| {
+            let wanted = interceptors_wanted(continent, prompts, ufo_tracker, alien_base);
+            (continent, wanted)
+        })
+        .filter(|(_, wanted)| *wanted > 0)
+        .collect::<Vec<_>>();
+    // Africa's bonus and the alien base are worth more than the other continents
+    unassigned.sort_by_key(|(continent, wanted)| {
+        let priority = **continent == Continent::Africa
+            || alien_base.is_some_and(|alien_base| alien_base.continent() == *continent);
+        (*wanted, !priority)
+    });
+    let mut remaining_budget = budget;
+    for (other, wanted) in unassigned {
+        if wanted > remaining_budget {
+            break;
+        }
+        if other == continent {
+            return wanted;
+        }
+        remaining_budget -= wanted;
+    }
+    0
+}
+
+// High UFO rolls are rerolled with Hyperwave Communication, and then with a satellite if there
+//   are any to spare
+fn roll_ufo_location<R: Rng>(forces: &mut Forces, tech_deck: &mut TechDeck, rng: &mut R) -> u32 {
+    let mut roll = roll_ufo_die(rng);
+    if roll >= 4 && tech_deck.use_tech(&Tech::HyperwaveCommunication) {
+        roll = roll_ufo_die(rng);
+    }
+    if roll >= 5 && forces.satellites() > 1 && forces.use_satellite() {
+        roll = roll_ufo_die(rng);
+    }
+    roll
+}
+
+// End of timed phase: use movement techs to send interceptors where UFOs are uncovered
+fn redeploy(ufos: &mut [u32; 3], forces: &mut Forces, tech_deck: &mut TechDeck) {
+    let uncovered = |ufos: &[u32; 3], forces: &Forces, continent: &Continent| {
+        ufos[continent.index()] as i32 - forces.deployed_on(continent) as i32
+    };
+    let most_uncovered = ALL_CONTINENTS
+        .iter()
+        .max_by_key(|continent| uncovered(ufos, forces, continent))
+        .unwrap();
+    let most_spare = ALL_CONTINENTS
+        .iter()
+        .min_by_key(|continent| uncovered(ufos, forces, continent))
+        .unwrap();
+    if uncovered(ufos, forces, most_uncovered) <= 0 || uncovered(ufos, forces, most_spare) >= 0 {
+        return;
+    }
+    if tech_deck.use_tech(&Tech::UFOTracking) {
+        let moved = 2
+            .min(MAX_INTERCEPTORS_PER_CONTINENT.saturating_sub(forces.deployed_on(most_uncovered)));
+        for _ in 0..moved {
+            if forces.recall(most_spare) {
+                forces.deploy(most_uncovered);
+            }
+        }
+    } else if tech_deck.use_tech(&Tech::UFONavigation) {
+        let moved = 3
+            .min(ufos[most_uncovered.index()])
+            .min(MAX_UFOS_PER_CONTINENT - ufos[most_spare.index()]);
+        ufos[most_uncovered.index()] -= moved;
+        ufos[most_spare.index()] += moved;
+    }
+}
+
+// Plays through the timed phase prompts, recording the forces and research in the game.
+//   Returns the UFOs on each continent and what was spent
+fn play_timed_phase<R: Rng>(
+    game: &mut Game,
+    prompts: &[TimedPhasePrompt],
+    rng: &mut R,
+) -> ([u32; 3], RoundSpending) {
+    let state = game.state();
+    let funds = game.funds();
+    let mut forces = state.forces.clone();
+    let mut tech_deck = state.tech_deck.clone();
+    let mut alien_base = state.alien_base.clone();
+    let mut ufo_tracker = UfoTracker::default();
+    let mut spending = RoundSpending::default();
+    for (index, prompt) in prompts.iter().enumerate() {
+        let mut roll = None;
+        match prompt {
+            TimedPhasePrompt::TakeIncome(_) if tech_deck.use_tech(&Tech::EleriumGenerator) => {
+                spending.elerium_generator_used = true;
+            }
+            TimedPhasePrompt::RollUFOLocation(_) => {
+                roll = Some(roll_ufo_location(&mut forces, &mut tech_deck, rng));
+            }
+            TimedPhasePrompt::AssignInterceptors(continent) => {
+                let budget = spare_funds(&funds, &spending).min(forces.reserves());
+                let wanted = plan_interceptors(
+                    continent,
+                    &prompts[index..],
+                    &ufo_tracker,
+                    alien_base.as_ref(),
+                    budget,
+                );
+                while forces.deployed_on(continent) < wanted
+                    && can_afford(&funds, &spending, INTERCEPTOR_PRICE)
+                    && forces.deploy(continent)
+                {
+                    spending.interceptors_deployed += 1;
+                }
+            }
+            TimedPhasePrompt::ChooseResearch => {
+                // Carries on with the current research, otherwise picks the cheapest
+                let researching = tech_deck.researching().is_some();
+                let cheapest = tech_deck
+                    .draw()
+                    .iter()
+                    .min_by_key(|tech| tech.cost())
+                    .cloned();
+                tech_deck.choose(cheapest.filter(|_| !researching).as_ref());
+            }
+            TimedPhasePrompt::SetResearchBudget if tech_deck.researching().is_some() => {
+                while spending.research_budget < MAX_RESEARCH_BUDGET
+                    && can_afford(&funds, &spending, 1 + FUNDS_KEPT_FROM_RESEARCH)
+                {
+                    spending.research_budget += 1;
+                }
+            }
+            TimedPhasePrompt::AlienBaseDiscovered(continent) => {
+                alien_base = Some(AlienBase::new(continent.clone()));
+            }
+            _ => {}
+        }
+        ufo_tracker.apply(prompt, roll);
+    }
+    let mut ufos = ALL_CONTINENTS.map(|continent| ufo_tracker.ufos(&continent).unwrap_or(0));
+    redeploy(&mut ufos, &mut forces, &mut tech_deck);
+    game.deploy_forces(forces);
+    game.choose_research(tech_deck);
+    game.complete_timed_phase(prompts);
+    (ufos, spending)
+}
+
+// Rolls an attempt at a task, using whichever owned techs help
+fn attempt_task<R: Rng>(
+    roller: &mut TaskRoller,
+    task: &Task,
+    success_dice: u32,
+    tech_deck: &mut TechDeck,
+    rng: &mut R,
+) -> TaskRoll {
+    roller.use_alien_intel(task, tech_deck);
+    let failed_dice = roller
+        .roll(task, success_dice, rng)
+        .success_dice
+        .iter()
+        .enumerate()
+        .filter(|(_, die)| **die < SUCCESS_DIE_THRESHOLD)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if !failed_dice.is_empty() {
+        roller.reroll_success_dice(&failed_dice, tech_deck, rng);
+    }
+    if roller
+        .last_roll()
+        .is_some_and(|roll| roll.alien_threat_triggered())
+    {
+        roller.reroll_alien_die(tech_deck, rng);
+    }
+    roller
+        .last_roll()
+        .cloned()
+        .expect("Task should have been rolled")
+}
+
+fn resolve_research<R: Rng>(board_state: &mut BoardState, tech_deck: &mut TechDeck, rng: &mut R) {
+    let remaining = match tech_deck.researching() {
+        Some(research) => research.tech.cost().saturating_sub(research.successes),
+        None => return,
+    };
+    let success_dice = board_state.spending.research_budget;
+    let mut roller = TaskRoller::new();
+    while success_dice > 0
+        && board_state.research_successes < remaining
+        && roller.alien_threat() <= RISK_TOLERANCE
+    {
+        let roll = attempt_task(&mut roller, &Task::Research, success_dice, tech_deck, rng);
+        board_state.research_successes += roll.successes();
+        if roll.alien_threat_triggered() {
+            board_state.research_destroyed = true;
+            return;
+        }
+    }
+}
+
+// Successes left over once a continent's UFOs are shot down go towards the alien base there
+fn resolve_ufo_defence<R: Rng>(
+    board_state: &mut BoardState,
+    forces: &Forces,
+    alien_base: &mut Option<AlienBase>,
+    tech_deck: &mut TechDeck,
+    rng: &mut R,
+) {
+    if let Some(continent) = ALL_CONTINENTS.iter().find(|continent| {
+        board_state.ufos_left_on(continent) > 0 && forces.deployed_on(continent) > 0
+    }) {
+        if tech_deck.use_tech(&Tech::Firestorm) {
+            board_state.ufos_left[continent.index()] -= 1;
+        }
+    }
+    for continent in ALL_CONTINENTS.iter() {
+        let index = continent.index();
+        let task = Task::UFODefence(continent.clone());
+        let mut roller = TaskRoller::new();
+        loop {
+            let mut alien_base_here = alien_base
+                .as_mut()
+                .filter(|alien_base| alien_base.continent() == continent);
+            let interceptors = forces.deployed_on(continent) - board_state.interceptors_lost[index];
+            let targets_remaining = board_state.ufos_left[index] > 0
+                || alien_base_here
+                    .as_ref()
+                    .is_some_and(|alien_base| !alien_base.is_destroyed());
+            if !targets_remaining || interceptors == 0 || roller.alien_threat() > RISK_TOLERANCE {
+                break;
+            }
+            let roll = attempt_task(&mut roller, &task, interceptors, tech_deck, rng);
+            let shot_down = roll.successes().min(board_state.ufos_left[index]);
+            board_state.ufos_left[index] -= shot_down;
+            if let Some(alien_base) = alien_base_here.as_mut() {
+                board_state.alien_base_damage +=
+                    alien_base.add_damage(roll.successes() - shot_down);
+            }
+            if roll.alien_threat_triggered() {
+                let mut lost = interceptors.div_ceil(2);
+                if tech_deck.use_tech(&Tech::DefenceMatrix) {
+                    lost -= 1;
+                }
+                board_state.interceptors_lost[index] += lost;
+            }
+        }
+    }
+}
+
+// Replaces lost interceptors and satellites with whatever can be spared once the round is paid for
+fn purchase_replacement_forces(game: &Game, board_state: &mut BoardState) {
+    let mut funds = game.funds();
+    funds.resolve_round(game.state().round, board_state);
+    let mut forces = game.state().forces.clone();
+    forces.resolve_round(board_state);
+    let spare_funds = funds.balance().saturating_sub(FUNDS_KEPT_BACK);
+    let spending = &mut board_state.spending;
+    spending.interceptors_purchased = STARTING_RESERVES
+        .saturating_sub(forces.reserves())
+        .min(spare_funds / INTERCEPTOR_PRICE);
+    spending.satellites_purchased = STARTING_SATELLITES
+        .saturating_sub(forces.satellites())
+        .min((spare_funds - spending.interceptors_purchased * INTERCEPTOR_PRICE) / SATELLITE_PRICE);
+}
+
+// Plays through the resolution phase prompts, returning the board state to submit for the round
+fn play_resolution_phase<R: Rng>(
+    game: &Game,
+    ufos: [u32; 3],
+    spending: RoundSpending,
+    rng: &mut R,
+) -> BoardState {
+    let state = game.state();
+    let mut tech_deck = state.tech_deck.clone();
+    let mut alien_base = state.alien_base.clone();
+    let mut board_state = BoardState {
+        panic: state.panic,
+        ufos_left: ufos,
+        spending,
+        ..BoardState::default()
+    };
+    let mut audit = None;
+    for prompt in generate_resolution_phase_prompts(state) {
+        match prompt {
+            ResolutionPhasePrompt::AuditSpending => {
+                audit = Some(game.funds().audit(&board_state.spending));
+            }
+            ResolutionPhasePrompt::ResolveResearch => {
+                resolve_research(&mut board_state, &mut tech_deck, rng);
+            }
+            ResolutionPhasePrompt::ResolveUFODefence => resolve_ufo_defence(
+                &mut board_state,
+                &state.forces,
+                &mut alien_base,
+                &mut tech_deck,
+                rng,
+            ),
+            ResolutionPhasePrompt::AssaultAlienBase => {
+                board_state.alien_base_destroyed =
+                    alien_base.as_ref().is_some_and(AlienBase::is_destroyed);
+            }
+            ResolutionPhasePrompt::IncreasePanic => {
+                let unpaid = audit.map(|audit| audit.unpaid).unwrap_or(0);
+                board_state
+                    .panic
+                    .increase(board_state.continents_increasing_panic().len() as u32 + unpaid);
+                if board_state.game_result().is_some() {
+                    break;
+                }
+            }
+            ResolutionPhasePrompt::PurchaseReplacementForces => {
+                purchase_replacement_forces(game, &mut board_state);
+            }
+            _ => {}
+        }
+    }
+    board_state.techs_used = ALL_TECHS
+        .iter()
+        .filter(|tech| tech_deck.is_exhausted(tech) && !state.tech_deck.is_exhausted(tech))
+        .cloned()
+        .collect();
+    board_state
+}
+
+// Plays a whole game, returning the result (if it finished) and the number of rounds played
+fn simulate_game(seed: u64, difficulty: Difficulty) -> (Option<GameResult>, u32) {
+    // The player's dice are kept apart from the game's own randomness
+    let rng = &mut StdRng::seed_from_u64(!seed);
+    let mut game = Game::new(seed, difficulty);
+    while game.state().round <= MAX_ROUNDS {
+        let round = game.state().round;
+        let prompts = game.start_round(0);
+        let (ufos, spending) = play_timed_phase(&mut game, &prompts, rng);
+        let board_state = play_resolution_phase(&game, ufos, spending, rng);
+        // Simulated games happen instantly, so every round is logged at the epoch
+        if let Some(result) = game.submit_board_state(board_state, 0) {
+            return (Some(result), round);
+        }
+    }
+    (None, MAX_ROUNDS)
+}
+
+fn parse_difficulty(input: &str) -> Option<Difficulty> {
+    ALL_DIFFICULTIES
+        .iter()
+        .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(input))
+        .cloned()
+}
+
+fn percentage(count: u32, total: u32) -> f64 {
+    100.0 * count as f64 / total as f64
+}
+
+fn main() {
+    let usage = "Usage: simulate [GAMES] [easy|normal|hard|impossible] [SEED]";
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let games = match args.first() {
+        Some(arg) => arg.parse::<u32>().expect(usage).max(1),
+        None => 10_000,
+    };
+    let difficulty = match args.get(1) {
+        Some(arg) => parse_difficulty(arg).expect(usage),
+        None => Difficulty::Normal,
+    };
+    let seed = match args.get(2) {
+        Some(arg) => arg.parse::<u64>().expect(usage),
+        None => random_seed(),
+    };
+
+    let seed_rng = &mut StdRng::seed_from_u64(seed);
+    let mut results: BTreeMap<String, u32> = BTreeMap::new();
+    let mut rounds_survived: BTreeMap<u32, u32> = BTreeMap::new();
+    for _ in 0..games {
+        let (result, rounds) = simulate_game(seed_rng.gen(), difficulty);
+        let result = match result {
+            Some(result) => result.to_string(),
+            None => "Unfinished".to_owned(),
+        };
+        *results.entry(result).or_default() += 1;
+        *rounds_survived.entry(rounds).or_default() += 1;
+    }
+
+    println!(
+        "Simulated {} games on {} (seed {})",
+        games, difficulty, seed
+    );
+    println!();
+    for result in ["Victory", "Pyrrhic Victory", "Defeat", "Unfinished"] {
+        let count = results.get(result).cloned().unwrap_or(0);
+        println!(
+            "{:>16}: {:>6} ({:5.1}%)",
+            result,
+            count,
+            percentage(count, games)
+        );
+    }
+    println!();
+    println!("Rounds played:");
+    let most_common = rounds_survived.values().cloned().max().unwrap_or(1);
+    for (rounds, count) in rounds_survived.iter() {
+        println!(
+            "{:>5}: {:>6} ({:5.1}%) {}",
+            rounds,
+            count,
+            percentage(*count, games),
+            "#".repeat((40 * count / most_common) as usize)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::ops::Range;
+    use test_case::test_case;
+    use xcom_1_card::{ALIEN_BASE_HEALTH, STARTING_FUNDS};
+
+    #[test_case("easy", Some(Difficulty::Easy))]
+    #[test_case("Impossible", Some(Difficulty::Impossible))]
+    #[test_case("medium", None)]
+    fn parse_difficulty_test(input: &str, expected: Option<Difficulty>) {
+        assert_eq!(expected, parse_difficulty(input));
+    }
+
+    #[test]
+    fn can_afford_test() {
+        let funds = FundsLedger::new();
+        let mut spending = RoundSpending::default();
+        assert!(can_afford(&funds, &spending, STARTING_FUNDS));
+        assert!(!can_afford(&funds, &spending, STARTING_FUNDS + 1));
+        spending.elerium_generator_used = true;
+        assert!(can_afford(&funds, &spending, STARTING_FUNDS + 1));
+    }
+
+    #[test_case(1, Difficulty::Easy)]
+    #[test_case(2, Difficulty::Normal)]
+    #[test_case(3, Difficulty::Impossible)]
+    fn simulate_game_test(seed: u64, difficulty: Difficulty) {
+        let (result, rounds) = simulate_game(seed, difficulty);
+        assert_eq!((result.clone(), rounds), simulate_game(seed, difficulty));
+        assert!((1..=MAX_ROUNDS).contains(&rounds));
+        if result.is_none() {
+            assert_eq!(MAX_ROUNDS, rounds);
+        }
+    }
+
+    #[test_case(4, [0, 2, 0])]
+    #[test_case(6, [4, 2, 0])]
+    #[test_case(12, [4, 2, 6])]
+    fn plan_interceptors_test(budget: u32, expected: [u32; 3]) {
+        let mut ufo_tracker = UfoTracker::default();
+        for (continent, roll) in ALL_CONTINENTS.iter().zip([3, 1, 6]) {
+            ufo_tracker.apply(
+                &TimedPhasePrompt::RollUFOLocation(continent.clone()),
+                Some(roll),
+            );
+        }
+        let prompts = ALL_CONTINENTS.map(TimedPhasePrompt::AssignInterceptors);
+        let planned = ALL_CONTINENTS
+            .map(|continent| plan_interceptors(&continent, &prompts, &ufo_tracker, None, budget));
+        assert_eq!(expected, planned);
+    }
+
+    // The player wins a fair share of games on the easier difficulties. It never wins on
+    //   Impossible, where the alien base is only discovered in round 7 and nearly every game has
+    //   already been lost to panic by then
+    #[test_case(Difficulty::Easy, 30..65)]
+    #[test_case(Difficulty::Normal, 10..35)]
+    #[test_case(Difficulty::Impossible, 0..1)]
+    fn win_rate_test(difficulty: Difficulty, expected_percentage: Range<u32>) {
+        let games = 400;
+        let seed_rng = &mut StdRng::seed_from_u64(42);
+        let wins = (0..games)
+            .filter(|_| {
+                let (result, _) = simulate_game(seed_rng.gen(), difficulty);
+                result.is_some_and(|result| result != GameResult::Defeat)
+            })
+            .count() as u32;
+        assert!(expected_percentage.contains(&(100 * wins / games)));
+    }
+
+    #[test_case(1)]
+    #[test_case(2)]
+    #[test_case(3)]
+    fn play_round_test(seed: u64) {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let mut game = Game::new(seed, Difficulty::Normal);
+        while game.result().is_none() && game.state().round <= MAX_ROUNDS {
+            let prompts = game.start_round(0);
+            let (ufos, spending) = play_timed_phase(&mut game, &prompts, rng);
+            let forces = game.state().forces.clone();
+            assert_eq!(0, game.funds().audit(&spending).unpaid);
+            assert_eq!(forces.total_deployed(), spending.interceptors_deployed);
+            assert!(ALL_CONTINENTS
+                .iter()
+                .all(|continent| forces.deployed_on(continent) <= MAX_INTERCEPTORS_PER_CONTINENT));
+
+            let board_state = play_resolution_phase(&game, ufos, spending, rng);
+            assert!(ALL_CONTINENTS.iter().all(|continent| {
+                board_state.ufos_left_on(continent) <= ufos[continent.index()]
+                    && board_state.interceptors_lost[continent.index()]
+                        <= forces.deployed_on(continent)
+            }));
+            assert!(board_state.alien_base_damage <= ALIEN_BASE_HEALTH);
+            if game.state().tech_deck.researching().is_none() {
+                assert_eq!(0, board_state.research_successes);
+            }
+            let round = game.state().round;
+            if game.submit_board_state(board_state, 0).is_none() {
+                assert_eq!(round + 1, game.state().round);
+            }
+        }
+    }
+}
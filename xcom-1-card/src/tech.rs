@@ -0,0 +1,374 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardState, Continent, ResolutionPhasePrompt, ResolvesPrompts};
+
+// Techs drawn from the stack to choose between at ChooseResearch
+pub const RESEARCH_CHOICES: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tech {
+    UFOTracking,
+    UFONavigation,
+    HyperwaveCommunication,
+    DefenceMatrix,
+    EMPCannon,
+    Firestorm,
+    AlienIntel,
+    CarapaceArmour,
+    EleriumGenerator,
+}
+
+pub const ALL_TECHS: [Tech; 9] = [
+    Tech::UFOTracking,
+    Tech::UFONavigation,
+    Tech::HyperwaveCommunication,
+    Tech::DefenceMatrix,
+    Tech::EMPCannon,
+    Tech::Firestorm,
+    Tech::AlienIntel,
+    Tech::CarapaceArmour,
+    Tech::EleriumGenerator,
+];
+
+impl Tech {
+    pub fn name(&self) -> String {
+        format!("{}", self)
+    }
+
+    // Number of success tokens needed to research the technology
+    pub fn cost(&self) -> u32 {
+        match self {
+            Self::UFOTracking | Self::DefenceMatrix | Self::AlienIntel => 1,
+            Self::UFONavigation | Self::EMPCannon | Self::CarapaceArmour => 2,
+            Self::HyperwaveCommunication | Self::Firestorm | Self::EleriumGenerator => 3,
+        }
+    }
+}
+
+impl Display for Tech {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UFOTracking => write!(f, "UFO Tracking"),
+            Self::UFONavigation => write!(f, "UFO Navigation"),
+            Self::HyperwaveCommunication => write!(f, "Hyperwave Communication"),
+            Self::DefenceMatrix => write!(f, "Defence Matrix"),
+            Self::EMPCannon => write!(f, "EMP Cannon"),
+            Self::Firestorm => write!(f, "Firestorm"),
+            Self::AlienIntel => write!(f, "Alien Intel"),
+            Self::CarapaceArmour => write!(f, "Carapace Armour"),
+            Self::EleriumGenerator => write!(f, "Elerium Generator"),
+        }
+    }
+}
+
+// The tech currently selected for research and the success tokens on it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Research {
+    pub tech: Tech,
+    pub successes: u32,
+}
+
+impl Display for Research {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}/{})", self.tech, self.successes, self.tech.cost())
+    }
+}
+
+// The technology token stack, along with the tech being researched and the techs that have been
+//   researched or destroyed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TechDeck {
+    // Top of the stack first
+    stack: VecDeque<Tech>,
+    // Drawn at ChooseResearch and waiting for the player to choose between them
+    drawn: Vec<Tech>,
+    researching: Option<Research>,
+    owned: Vec<Tech>,
+    destroyed: Vec<Tech>,
+    // Owned techs that have been used this round
+    #[serde(default)]
+    exhausted: Vec<Tech>,
+}
+
+impl TechDeck {
+    fn from_stack(stack: Vec<Tech>) -> Self {
+        Self {
+            stack: stack.into(),
+            drawn: Vec::new(),
+            researching: None,
+            owned: Vec::new(),
+            destroyed: Vec::new(),
+            exhausted: Vec::new(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn owning(owned: Vec<Tech>) -> Self {
+        Self {
+            owned,
+            ..Self::from_stack(Vec::new())
+        }
+    }
+
+    pub fn shuffled(rng: &mut impl Rng) -> Self {
+        let mut stack = ALL_TECHS.to_vec();
+        stack.shuffle(rng);
+        Self::from_stack(stack)
+    }
+
+    pub fn stack(&self) -> &VecDeque<Tech> {
+        &self.stack
+    }
+
+    pub fn drawn(&self) -> &[Tech] {
+        &self.drawn
+    }
+
+    pub fn researching(&self) -> Option<&Research> {
+        self.researching.as_ref()
+    }
+
+    pub fn owned(&self) -> &[Tech] {
+        &self.owned
+    }
+
+    pub fn destroyed(&self) -> &[Tech] {
+        &self.destroyed
+    }
+
+    pub fn is_exhausted(&self, tech: &Tech) -> bool {
+        self.exhausted.contains(tech)
+    }
+
+    // Each owned tech can be used once per round
+    pub fn use_tech(&mut self, tech: &Tech) -> bool {
+        if self.owned.contains(tech) && !self.is_exhausted(tech) {
+            self.exhausted.push(tech.clone());
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.exhausted.clear();
+    }
+
+    // Draws the techs to choose between, unless they've already been drawn
+    pub fn draw(&mut self) -> &[Tech] {
+        while self.drawn.len() < RESEARCH_CHOICES {
+            match self.stack.pop_front() {
+                Some(tech) => self.drawn.push(tech),
+                None => break,
+            }
+        }
+        &self.drawn
+    }
+
+    // Starts researching one of the drawn techs, or carries on with the current research if none
+    //   is chosen. Anything not being researched goes back to the bottom of the stack, losing
+    //   its success tokens
+    pub fn choose(&mut self, tech: Option<&Tech>) -> bool {
+        let chosen = match tech {
+            Some(tech) => match self.drawn.iter().position(|drawn| drawn == tech) {
+                Some(index) => Some(self.drawn.remove(index)),
+                None => return false,
+            },
+            None => None,
+        };
+        if let Some(tech) = chosen {
+            if let Some(previous) = self.researching.replace(Research { tech, successes: 0 }) {
+                self.stack.push_back(previous.tech);
+            }
+        }
+        self.stack.extend(self.drawn.drain(..));
+        true
+    }
+
+    // Adds success tokens to the current research, returning the tech if it was completed
+    pub fn add_successes(&mut self, successes: u32) -> Option<Tech> {
+        let research = self.researching.as_mut()?;
+        research.successes += successes;
+        if research.successes >= research.tech.cost() {
+            let tech = self.researching.take()?.tech;
+            self.owned.push(tech.clone());
+            Some(tech)
+        } else {
+            None
+        }
+    }
+
+    // The alien threat during research destroys the tech being researched, if there is one
+    pub fn destroy_research(&mut self) -> Option<Tech> {
+        let tech = self.researching.take()?.tech;
+        self.destroyed.push(tech.clone());
+        Some(tech)
+    }
+
+    // When the research was completed on the same attempt the alien threat hit, the next
+    //   unresearched tech in the stack is destroyed instead
+    pub fn destroy_next_unresearched(&mut self) -> Option<Tech> {
+        let tech = self.stack.pop_front()?;
+        self.destroyed.push(tech.clone());
+        Some(tech)
+    }
+
+    // Eurasia's bonus adds a success token, selecting the top tech of the stack if nothing is
+    //   being researched
+    pub fn add_bonus_success(&mut self) -> Option<Tech> {
+        if self.researching.is_none() {
+            let tech = self.stack.pop_front()?;
+            self.researching = Some(Research { tech, successes: 0 });
+        }
+        self.add_successes(1)
+    }
+}
+
+impl ResolvesPrompts for TechDeck {
+    // Applies the changes a resolution phase prompt makes to the research
+    fn resolve_prompt(&mut self, prompt: &ResolutionPhasePrompt, board_state: &BoardState) {
+        match prompt {
+            ResolutionPhasePrompt::ResolveResearch => {
+                let completed = self.add_successes(board_state.research_successes);
+                if board_state.research_destroyed {
+                    if completed.is_some() {
+                        self.destroy_next_unresearched();
+                    } else {
+                        self.destroy_research();
+                    }
+                }
+            }
+            ResolutionPhasePrompt::ResolveContinentBonuses
+                if board_state
+                    .earned_continent_bonuses()
+                    .contains(&Continent::Eurasia) =>
+            {
+                self.add_bonus_success();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn deck(stack: Vec<Tech>) -> TechDeck {
+        TechDeck::from_stack(stack)
+    }
+
+    #[test]
+    fn choose_returns_others_to_bottom_test() {
+        let mut tech_deck = deck(vec![Tech::AlienIntel, Tech::Firestorm, Tech::EMPCannon]);
+        assert_eq!(&[Tech::AlienIntel, Tech::Firestorm], tech_deck.draw());
+        // Drawing again before choosing doesn't draw more
+        assert_eq!(2, tech_deck.draw().len());
+        assert!(tech_deck.choose(Some(&Tech::Firestorm)));
+        assert_eq!(
+            Some(&Research {
+                tech: Tech::Firestorm,
+                successes: 0
+            }),
+            tech_deck.researching()
+        );
+        assert_eq!(
+            vec![Tech::EMPCannon, Tech::AlienIntel],
+            tech_deck.stack().iter().cloned().collect::<Vec<_>>()
+        );
+        assert!(tech_deck.drawn().is_empty());
+    }
+
+    #[test]
+    fn switching_research_discards_successes_test() {
+        let mut tech_deck = deck(vec![Tech::Firestorm, Tech::EMPCannon, Tech::AlienIntel]);
+        tech_deck.draw();
+        tech_deck.choose(Some(&Tech::Firestorm));
+        tech_deck.add_successes(2);
+
+        tech_deck.draw();
+        assert!(!tech_deck.choose(Some(&Tech::UFOTracking)));
+        tech_deck.choose(None);
+        assert_eq!(2, tech_deck.researching().unwrap().successes);
+
+        tech_deck.draw();
+        tech_deck.choose(Some(&Tech::AlienIntel));
+        assert!(tech_deck.stack().contains(&Tech::Firestorm));
+        assert_eq!(0, tech_deck.researching().unwrap().successes);
+    }
+
+    #[test_case(1, None)]
+    #[test_case(2, Some(Tech::EMPCannon))]
+    #[test_case(4, Some(Tech::EMPCannon))]
+    fn add_successes_test(successes: u32, expected: Option<Tech>) {
+        let mut tech_deck = deck(vec![Tech::EMPCannon]);
+        tech_deck.draw();
+        tech_deck.choose(Some(&Tech::EMPCannon));
+        assert_eq!(expected, tech_deck.add_successes(successes));
+        assert_eq!(expected.is_some(), tech_deck.researching().is_none());
+        assert_eq!(expected.into_iter().collect::<Vec<_>>(), tech_deck.owned());
+    }
+
+    #[test]
+    fn destroy_research_test() {
+        let mut tech_deck = deck(vec![Tech::AlienIntel, Tech::Firestorm, Tech::EMPCannon]);
+        tech_deck.draw();
+        tech_deck.choose(Some(&Tech::Firestorm));
+        assert_eq!(Some(Tech::Firestorm), tech_deck.destroy_research());
+
+        // Nothing is destroyed when nothing is being researched
+        assert_eq!(None, tech_deck.destroy_research());
+        assert_eq!(&[Tech::Firestorm], tech_deck.destroyed());
+
+        // Completing the research on the same attempt destroys the top of the stack instead
+        tech_deck.draw();
+        tech_deck.choose(Some(&Tech::EMPCannon));
+        tech_deck.add_successes(2);
+        assert_eq!(
+            Some(Tech::AlienIntel),
+            tech_deck.destroy_next_unresearched()
+        );
+        assert_eq!(&[Tech::EMPCannon], tech_deck.owned());
+        assert_eq!(&[Tech::Firestorm, Tech::AlienIntel], tech_deck.destroyed());
+    }
+
+    #[test]
+    fn bonus_success_selects_top_of_stack_test() {
+        let mut tech_deck = deck(vec![Tech::AlienIntel, Tech::Firestorm]);
+        assert_eq!(Some(Tech::AlienIntel), tech_deck.add_bonus_success());
+        assert_eq!(None, tech_deck.add_bonus_success());
+        assert_eq!(
+            Some(&Research {
+                tech: Tech::Firestorm,
+                successes: 1
+            }),
+            tech_deck.researching()
+        );
+    }
+
+    #[test]
+    fn use_tech_once_per_round_test() {
+        let mut tech_deck = deck(vec![Tech::AlienIntel, Tech::Firestorm]);
+        assert!(!tech_deck.use_tech(&Tech::AlienIntel));
+        tech_deck.add_bonus_success();
+        assert!(tech_deck.use_tech(&Tech::AlienIntel));
+        assert!(!tech_deck.use_tech(&Tech::AlienIntel));
+        assert!(tech_deck.is_exhausted(&Tech::AlienIntel));
+        tech_deck.refresh();
+        assert!(!tech_deck.is_exhausted(&Tech::AlienIntel));
+    }
+
+    #[test]
+    fn shuffled_has_every_tech_test() {
+        let mut rng = crate::round_rng(1234, 0);
+        let tech_deck = TechDeck::shuffled(&mut rng);
+        assert_eq!(ALL_TECHS.len(), tech_deck.stack().len());
+        assert!(ALL_TECHS
+            .iter()
+            .all(|tech| tech_deck.stack().contains(tech)));
+    }
+}
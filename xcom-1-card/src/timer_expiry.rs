@@ -0,0 +1,55 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+// Panic added when the timer runs out under the panic rule
+pub const TIMER_EXPIRY_PANIC: u32 = 1;
+
+// What happens when the timed phase runs out of time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimerExpiryRule {
+    #[default]
+    Suggestion,
+    EndPhase,
+    IncreasePanic,
+}
+
+pub const ALL_TIMER_EXPIRY_RULES: [TimerExpiryRule; 3] = [
+    TimerExpiryRule::Suggestion,
+    TimerExpiryRule::EndPhase,
+    TimerExpiryRule::IncreasePanic,
+];
+
+impl TimerExpiryRule {
+    pub fn lowercase(&self) -> String {
+        match self {
+            Self::Suggestion => "suggestion",
+            Self::EndPhase => "end-phase",
+            Self::IncreasePanic => "increase-panic",
+        }
+        .to_owned()
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Suggestion => "The timer is only a guide. Play carries on once it runs out.",
+            Self::EndPhase => {
+                "Time's up means time's up. The phase ends immediately and any prompts not yet reached resolve by themselves, with their UFO dice rolled for you. Continents that haven't been assigned interceptors get none and no new research is chosen."
+            }
+            Self::IncreasePanic => {
+                "Play carries on once the timer runs out, but the Panic Track increases one space."
+            }
+        }
+        .to_owned()
+    }
+}
+
+impl Display for TimerExpiryRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Suggestion => write!(f, "Suggestion"),
+            Self::EndPhase => write!(f, "End Phase"),
+            Self::IncreasePanic => write!(f, "Increase Panic"),
+        }
+    }
+}
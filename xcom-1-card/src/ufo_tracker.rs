@@ -0,0 +1,157 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Continent, TimedPhasePrompt};
+
+// UFOs are counted on a d6, so a continent can't have more than this
+pub const MAX_UFOS_PER_CONTINENT: u32 = 6;
+
+pub fn roll_ufo_die(rng: &mut impl Rng) -> u32 {
+    rng.gen_range(1..=MAX_UFOS_PER_CONTINENT)
+}
+
+// Rolls the UFO dice for any of the prompts without a result, e.g. the ones the player didn't
+//   reach before the timer ran out. The rolls line up with the prompts
+pub fn roll_missing_ufo_dice(
+    prompts: &[TimedPhasePrompt],
+    rolls: &mut [Option<u32>],
+    rng: &mut impl Rng,
+) {
+    for (prompt, roll) in prompts.iter().zip(rolls.iter_mut()) {
+        if let (TimedPhasePrompt::RollUFOLocation(_), None) = (prompt, &roll) {
+            *roll = Some(roll_ufo_die(rng));
+        }
+    }
+}
+
+// Follows the UFO dice on the world map through a timed phase. A continent's count is unknown
+//   if its die was rolled without the result being entered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UfoTracker {
+    ufos: [Option<u32>; 3],
+}
+
+impl Default for UfoTracker {
+    // The world map is cleared of UFO dice at the end of every round
+    fn default() -> Self {
+        Self { ufos: [Some(0); 3] }
+    }
+}
+
+impl UfoTracker {
+    // Applies the prompts in order, using the die result entered for each roll prompt (if any)
+    pub fn from_prompts(prompts: &[TimedPhasePrompt], rolls: &[Option<u32>]) -> Self {
+        let mut tracker = Self::default();
+        for (index, prompt) in prompts.iter().enumerate() {
+            tracker.apply(prompt, rolls.get(index).cloned().flatten());
+        }
+        tracker
+    }
+
+    pub fn apply(&mut self, prompt: &TimedPhasePrompt, roll: Option<u32>) {
+        match prompt {
+            TimedPhasePrompt::RollUFOLocation(continent) => {
+                self.ufos[continent.index()] =
+                    roll.map(|roll| roll.clamp(1, MAX_UFOS_PER_CONTINENT));
+            }
+            TimedPhasePrompt::AddUFOsToLocation(continent, amount) => {
+                let ufos = &mut self.ufos[continent.index()];
+                *ufos = ufos.map(|ufos| {
+                    (ufos as i32 + amount).clamp(0, MAX_UFOS_PER_CONTINENT as i32) as u32
+                });
+            }
+            TimedPhasePrompt::SwapUFOLocations(from, to) => {
+                self.ufos.swap(from.index(), to.index());
+            }
+            _ => {}
+        }
+    }
+
+    pub fn ufos(&self, continent: &Continent) -> Option<u32> {
+        self.ufos[continent.index()]
+    }
+
+    // Only known if every continent's count is known
+    pub fn total(&self) -> Option<u32> {
+        self.ufos.iter().cloned().sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ALL_CONTINENTS;
+    use rand::{rngs::StdRng, SeedableRng};
+    use test_case::test_case;
+
+    #[test_case(
+        vec![TimedPhasePrompt::RollUFOLocation(Continent::Africa)],
+        vec![Some(4)],
+        [Some(0), Some(4), Some(0)]
+    )]
+    #[test_case(
+        vec![TimedPhasePrompt::RollUFOLocation(Continent::Africa)],
+        vec![None],
+        [Some(0), None, Some(0)]
+    )]
+    #[test_case(
+        vec![
+            TimedPhasePrompt::RollUFOLocation(Continent::Africa),
+            TimedPhasePrompt::AddUFOsToLocation(Continent::Africa, 2),
+        ],
+        vec![Some(5)],
+        [Some(0), Some(6), Some(0)]
+    )]
+    #[test_case(
+        vec![
+            TimedPhasePrompt::RollUFOLocation(Continent::Africa),
+            TimedPhasePrompt::RollUFOLocation(Continent::Eurasia),
+            TimedPhasePrompt::SwapUFOLocations(Continent::Africa, Continent::Eurasia),
+            TimedPhasePrompt::AddUFOsToLocation(Continent::Eurasia, 1),
+        ],
+        vec![Some(2), None],
+        [Some(0), None, Some(3)]
+    )]
+    fn from_prompts_test(
+        prompts: Vec<TimedPhasePrompt>,
+        rolls: Vec<Option<u32>>,
+        expected: [Option<u32>; 3],
+    ) {
+        let tracker = UfoTracker::from_prompts(&prompts, &rolls);
+        let ufos: Vec<Option<u32>> = ALL_CONTINENTS
+            .iter()
+            .map(|continent| tracker.ufos(continent))
+            .collect();
+        assert_eq!(expected.to_vec(), ufos);
+    }
+
+    #[test]
+    fn total_test() {
+        let prompts = vec![
+            TimedPhasePrompt::RollUFOLocation(Continent::America),
+            TimedPhasePrompt::RollUFOLocation(Continent::Eurasia),
+        ];
+        assert_eq!(
+            Some(7),
+            UfoTracker::from_prompts(&prompts, &[Some(3), Some(4)]).total()
+        );
+        assert_eq!(
+            None,
+            UfoTracker::from_prompts(&prompts, &[Some(3), None]).total()
+        );
+    }
+
+    #[test]
+    fn roll_missing_ufo_dice_test() {
+        let prompts = vec![
+            TimedPhasePrompt::RollUFOLocation(Continent::America),
+            TimedPhasePrompt::ChooseResearch,
+            TimedPhasePrompt::RollUFOLocation(Continent::Eurasia),
+        ];
+        let mut rolls = vec![Some(2), None, None];
+        roll_missing_ufo_dice(&prompts, &mut rolls, &mut StdRng::seed_from_u64(7));
+        assert_eq!(Some(2), rolls[0]);
+        assert_eq!(None, rolls[1]);
+        assert!(rolls[2].is_some_and(|roll| (1..=MAX_UFOS_PER_CONTINENT).contains(&roll)));
+    }
+}